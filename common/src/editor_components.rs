@@ -0,0 +1,35 @@
+use bevy::{
+    app::{App, Plugin},
+    ecs::{component::Component, reflect::ReflectComponent},
+    reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+/// Keeps the entity and its descendants from being picked or moved in the editor.
+/// Saved with the scene, but games have no use for it
+#[derive(Component, Reflect, Default, Clone, Copy, PartialEq, Debug)]
+#[reflect(Component, Default)]
+pub struct Locked;
+
+/// Entity belongs to the editor itself, like its camera or window, rather than to the edited scene.
+/// Such entities are hidden from the editor panels and aren't saved
+#[derive(Component, Reflect, Default, Clone, Copy, PartialEq, Debug)]
+#[reflect(Component, Default)]
+pub struct EditorOnly;
+
+/// Position of a root entity in the editor hierarchy, which otherwise follows the storage order.
+/// Saved with the scene, so everyone opening it sees the same order
+#[derive(Component, Reflect, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[reflect(Component, Default)]
+pub struct RootOrder(pub u32);
+
+/// Registers components, that only the editor acts on,
+/// so scenes containing them can be loaded by games too
+pub struct EditorComponentsPlugin;
+
+impl Plugin for EditorComponentsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Locked>()
+            .register_type::<EditorOnly>()
+            .register_type::<RootOrder>();
+    }
+}
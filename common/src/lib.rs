@@ -0,0 +1,2 @@
+pub mod editor_components;
+pub mod scene_assets;
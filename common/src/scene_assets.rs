@@ -0,0 +1,129 @@
+use bevy::{
+    app::{App, Plugin, PreUpdate},
+    asset::{AssetServer, Assets},
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::Changed,
+        reflect::ReflectComponent,
+        system::{Commands, Query, Res, ResMut},
+    },
+    math::{
+        primitives::{Cuboid, Sphere},
+        Vec2, Vec3,
+    },
+    pbr::StandardMaterial,
+    reflect::{std_traits::ReflectDefault, Reflect},
+    render::{
+        color::Color,
+        mesh::{Mesh, Meshable, PlaneMeshBuilder},
+        primitives::Aabb,
+    },
+};
+
+/// Describes how to build the mesh of an entity.
+/// Scenes store this instead of the `Handle<Mesh>`, which can't be serialized
+#[derive(Component, Reflect, Clone, PartialEq, Debug)]
+#[reflect(Component, Default)]
+pub enum MeshSource {
+    Cuboid {
+        half_size: Vec3,
+    },
+    Plane {
+        half_size: Vec2,
+    },
+    Sphere {
+        radius: f32,
+    },
+    /// Path of mesh asset, e.g. `models/tree.glb#Mesh0/Primitive0`
+    Asset(String),
+}
+
+impl Default for MeshSource {
+    fn default() -> Self {
+        MeshSource::Cuboid {
+            half_size: Vec3::splat(0.5),
+        }
+    }
+}
+
+/// Describes the material of an entity.
+/// Scenes store this instead of the `Handle<StandardMaterial>`, which can't be serialized
+#[derive(Component, Reflect, Clone, PartialEq, Debug)]
+#[reflect(Component, Default)]
+pub struct MaterialSource {
+    pub base_color: Color,
+    pub metallic: f32,
+    pub perceptual_roughness: f32,
+}
+
+impl Default for MaterialSource {
+    fn default() -> Self {
+        MaterialSource {
+            base_color: Color::WHITE,
+            metallic: 0.,
+            perceptual_roughness: 0.5,
+        }
+    }
+}
+
+impl From<Color> for MaterialSource {
+    fn from(base_color: Color) -> Self {
+        MaterialSource {
+            base_color,
+            ..Default::default()
+        }
+    }
+}
+
+fn build_meshes(
+    mut commands: Commands,
+    query: Query<(Entity, &MeshSource), Changed<MeshSource>>,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (entity, source) in query.iter() {
+        let mesh = match source {
+            MeshSource::Cuboid { half_size } => meshes.add(Cuboid {
+                half_size: *half_size,
+            }),
+            MeshSource::Plane { half_size } => meshes.add(PlaneMeshBuilder {
+                half_size: *half_size,
+                ..Default::default()
+            }),
+            MeshSource::Sphere { radius } => meshes.add(Sphere::new(*radius).mesh()),
+            MeshSource::Asset(path) => asset_server.load(path.clone()),
+        };
+
+        // Bounds are only computed for entities without them, so the old ones have to go
+        commands.entity(entity).insert(mesh).remove::<Aabb>();
+    }
+}
+
+fn build_materials(
+    mut commands: Commands,
+    query: Query<(Entity, &MaterialSource), Changed<MaterialSource>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (entity, source) in query.iter() {
+        let material = materials.add(StandardMaterial {
+            base_color: source.base_color,
+            metallic: source.metallic,
+            perceptual_roughness: source.perceptual_roughness,
+            ..Default::default()
+        });
+
+        commands.entity(entity).insert(material);
+    }
+}
+
+/// Creates asset handles from their sources, both in the editor and in the game loading its scenes
+pub struct SceneAssetsPlugin;
+
+impl Plugin for SceneAssetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<MeshSource>()
+            .register_type::<MaterialSource>()
+            .add_systems(PreUpdate, (build_meshes, build_materials));
+    }
+}
@@ -2,7 +2,10 @@ use bevy::{
     app::{Plugin, Update},
     asset::{Assets, Handle},
     ecs::{
+        entity::{Entities, Entity},
         event::EventReader,
+        query::{Or, With},
+        schedule::IntoSystemConfigs,
         system::{Query, Res, ResMut, Resource},
     },
     gizmos::gizmos::Gizmos,
@@ -13,13 +16,73 @@ use bevy::{
 };
 use bevy_egui::EguiContexts;
 use bevy_mod_picking::events::{Click, Pointer};
+use common::editor_components::EditorOnly;
+
+use crate::{
+    box_selection::{update_box_selection, BoxSelection},
+    deleted::Deleted,
+    history::History,
+    input_map::{EditorInput, InputAction},
+    lock::LockedEntities,
+    manipulator::Manipulator,
+};
 
-#[derive(Resource, Default)]
+#[derive(Resource, Default, Clone, PartialEq, Debug)]
 pub struct ActiveSelection {
-    pub entity_id: u32,
-    pub transform: Transform,
-    pub scale: Vec3,
-    pub has_selection: bool,
+    /// Selected entities in the order they were selected.
+    /// Last one is the active entity, which is shown in inspector
+    entities: Vec<Entity>,
+}
+
+impl ActiveSelection {
+    pub fn active(&self) -> Option<Entity> {
+        self.entities.last().copied()
+    }
+
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    pub fn is_selected(&self, entity: Entity) -> bool {
+        self.entities.contains(&entity)
+    }
+
+    pub fn is_active(&self, entity: Entity) -> bool {
+        self.active() == Some(entity)
+    }
+
+    /// Replaces whole selection with a single entity
+    pub fn select(&mut self, entity: Entity) {
+        self.entities.clear();
+        self.entities.push(entity);
+    }
+
+    /// Adds entity to the selection and makes it active
+    pub fn add(&mut self, entity: Entity) {
+        self.entities.retain(|selected| *selected != entity);
+        self.entities.push(entity);
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        self.entities.retain(|selected| *selected != entity);
+    }
+
+    /// Removes entity if it was selected, otherwise adds it
+    pub fn toggle(&mut self, entity: Entity) {
+        if self.is_selected(entity) {
+            self.remove(entity);
+        } else {
+            self.add(entity);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entities.clear();
+    }
+
+    pub fn retain(&mut self, f: impl FnMut(&Entity) -> bool) {
+        self.entities.retain(f);
+    }
 }
 
 const DEFAULT_SCALE: Vec3 = Vec3::new(0.5, 0.5, 0.5);
@@ -27,7 +90,7 @@ const DEFAULT_SCALE: Vec3 = Vec3::new(0.5, 0.5, 0.5);
 pub fn extract_scale_and_transform(
     transform: Option<&GlobalTransform>,
     mesh: Option<&Handle<Mesh>>,
-    meshes: &ResMut<Assets<Mesh>>,
+    meshes: &Assets<Mesh>,
 ) -> (Vec3, Transform) {
     let transform = match transform {
         Some(transform) => Transform::from(*transform),
@@ -48,57 +111,103 @@ fn try_to_select_target(
     mut contexts: EguiContexts,
     mut pointer_click: EventReader<Pointer<Click>>,
     input_mouse: Res<ButtonInput<MouseButton>>,
-    mut active_selection: ResMut<ActiveSelection>,
-    query: Query<(Option<&GlobalTransform>, Option<&Handle<Mesh>>)>,
-    meshes: ResMut<Assets<Mesh>>,
+    input: EditorInput,
+    box_selection: Res<BoxSelection>,
+    manipulator: Res<Manipulator>,
+    active_selection: Res<ActiveSelection>,
+    locked: LockedEntities,
+    editor_entities: Query<(), Or<(With<EditorOnly>, With<Deleted>)>>,
+    mut history: ResMut<History>,
 ) {
+    // Box selection and manipulator decide by themselves what to do with the drag
+    // Camera bindings may use the left button as well
+    if box_selection.is_active() || manipulator.is_active() || input.is_navigating() {
+        pointer_click.clear();
+        return;
+    }
+
     let pointer_over_egui = contexts.ctx_mut().is_pointer_over_area();
 
     if pointer_over_egui {
         return;
     }
 
-    if pointer_click.is_empty() {
-        if input_mouse.pressed(MouseButton::Left) && !pointer_over_egui {
-            active_selection.has_selection = false;
+    let toggle = input.held(InputAction::ToggleSelection);
+    let add = input.held(InputAction::AddToSelection);
+
+    // Locked entities can't be picked, clicking them is the same as clicking into empty space
+    let targets = pointer_click
+        .read()
+        .map(|event| event.target)
+        .filter(|target| !locked.is_locked(*target) && !editor_entities.contains(*target))
+        .collect::<Vec<_>>();
+
+    if targets.is_empty() {
+        // Clicking into empty space
+        if input_mouse.just_released(MouseButton::Left) && !toggle && !add {
+            history.change_selection(&active_selection, ActiveSelection::clear);
         }
 
         return;
     }
 
-    for event in pointer_click.read() {
-        if let Ok((transform, mesh)) = query.get(event.target) {
-            active_selection.has_selection = true;
-            active_selection.entity_id = event.target.index();
+    history.change_selection(&active_selection, |selection| {
+        for target in targets {
+            if toggle {
+                selection.toggle(target);
+            } else if add {
+                selection.add(target);
+            } else {
+                selection.select(target);
+            }
+        }
+    });
+}
 
-            let (scale, transform) = extract_scale_and_transform(transform, mesh, &meshes);
+/**
+ * Drops entities from the selection as soon as they are despawned or deleted
+ */
+fn clear_despawned_selection(
+    mut active_selection: ResMut<ActiveSelection>,
+    entities: &Entities,
+    deleted: Query<(), With<Deleted>>,
+) {
+    let exists = |entity: &Entity| entities.contains(*entity) && !deleted.contains(*entity);
 
-            active_selection.transform = transform;
-            active_selection.scale = scale;
-        }
+    if !active_selection.entities().iter().all(exists) {
+        active_selection.retain(exists);
     }
 }
 
-fn draw_selection(active_selection: ResMut<ActiveSelection>, mut gizmos: Gizmos) {
-    if !active_selection.has_selection {
-        return;
-    }
+fn draw_selection(
+    active_selection: Res<ActiveSelection>,
+    query: Query<(Option<&GlobalTransform>, Option<&Handle<Mesh>>)>,
+    meshes: Res<Assets<Mesh>>,
+    mut gizmos: Gizmos,
+) {
+    for entity in active_selection.entities() {
+        // Reading from the live world, so gizmo follows the entity when it moves
+        let Ok((transform, mesh)) = query.get(*entity) else {
+            continue;
+        };
 
-    let position = active_selection.transform.translation;
+        let (scale, transform) = extract_scale_and_transform(transform, mesh, &meshes);
 
-    let scale = active_selection.scale * 2.;
+        let position = transform.translation;
 
-    let transform = Transform::from_xyz(position.x, position.y, position.z).with_scale(scale);
+        let scale = scale * 2.;
 
-    gizmos.cuboid(transform, Color::RED);
+        let transform = Transform::from_xyz(position.x, position.y, position.z).with_scale(scale);
 
-    gizmos.ray(transform.translation, transform.up().into(), Color::GREEN);
-    gizmos.ray(
-        transform.translation,
-        transform.forward().into(),
-        Color::BLUE,
-    );
-    gizmos.ray(transform.translation, transform.right().into(), Color::RED);
+        // Active entity is highlighted differently from the rest of the selection
+        let color = if active_selection.is_active(*entity) {
+            Color::RED
+        } else {
+            Color::ORANGE
+        };
+
+        gizmos.cuboid(transform, color);
+    }
 }
 
 pub struct ActiveSelectionPlugin;
@@ -106,7 +215,13 @@ pub struct ActiveSelectionPlugin;
 impl Plugin for ActiveSelectionPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.init_resource::<ActiveSelection>()
-            .add_systems(Update, draw_selection)
-            .add_systems(Update, (try_to_select_target,));
+            .add_systems(Update, draw_selection.after(clear_despawned_selection))
+            .add_systems(
+                Update,
+                (
+                    try_to_select_target.after(update_box_selection),
+                    clear_despawned_selection,
+                ),
+            );
     }
 }
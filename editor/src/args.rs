@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+
+use bevy::{ecs::system::Resource, log::warn};
+
+/// Command line arguments of the editor:
+/// `editor [--sample] [--stress COUNT] [scene.scn.ron]`
+#[derive(Resource, Default, Debug)]
+pub struct EditorArgs {
+    /// Scene file to open on startup
+    pub scene: Option<PathBuf>,
+    /// Start with the sample scene, if no scene file is given
+    pub sample: bool,
+    /// Start with that many generated entities and log frame timings,
+    /// to measure the editor on large worlds
+    pub stress: Option<usize>,
+}
+
+impl EditorArgs {
+    pub fn from_env() -> Self {
+        let mut args = EditorArgs::default();
+
+        let mut env_args = std::env::args().skip(1);
+
+        while let Some(arg) = env_args.next() {
+            match arg.as_str() {
+                "--sample" => args.sample = true,
+                "--stress" => match env_args.next().map(|count| count.parse()) {
+                    Some(Ok(count)) => args.stress = Some(count),
+                    _ => warn!("--stress expects a number of entities"),
+                },
+                flag if flag.starts_with("--") => warn!("Unknown argument {flag}"),
+                path => args.scene = Some(PathBuf::from(path)),
+            }
+        }
+
+        args
+    }
+}
@@ -0,0 +1,203 @@
+use bevy::{
+    app::{Plugin, Update},
+    asset::{Assets, Handle},
+    ecs::{
+        entity::Entity,
+        query::{With, Without},
+        schedule::IntoSystemConfigs,
+        system::{Query, Res, ResMut, Resource},
+    },
+    input::{mouse::MouseButton, ButtonInput},
+    math::{Rect, Vec2, Vec3},
+    render::{camera::Camera, mesh::Mesh},
+    transform::components::GlobalTransform,
+    window::{PrimaryWindow, Window},
+};
+use bevy_egui::{
+    egui::{self, Color32, Id, LayerId, Order, Rounding, Stroke},
+    EguiContexts,
+};
+use bevy_mod_picking::prelude::Pickable;
+use common::editor_components::EditorOnly;
+
+use crate::{
+    active_selection::{extract_scale_and_transform, ActiveSelection},
+    camera::{viewport_origin, PanOrbitCamera},
+    deleted::Deleted,
+    history::History,
+    input_map::{EditorInput, InputAction},
+    lock::LockedEntities,
+    manipulator::{update_manipulator, Manipulator},
+};
+
+/// Distance in logical pixels the pointer has to travel before press is treated as a drag
+const DRAG_THRESHOLD: f32 = 4.;
+
+#[derive(Resource, Default)]
+pub struct BoxSelection {
+    start: Option<Vec2>,
+    current: Vec2,
+    /// True while dragging and on the frame drag was released
+    active: bool,
+}
+
+impl BoxSelection {
+    /// Clicks should not change the selection while box is dragged
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    fn rect(&self) -> Option<Rect> {
+        self.start
+            .filter(|_| self.active)
+            .map(|start| Rect::from_corners(start, self.current))
+    }
+}
+
+pub fn update_box_selection(
+    mut contexts: EguiContexts,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    input_mouse: Res<ButtonInput<MouseButton>>,
+    input: EditorInput,
+    manipulator: Res<Manipulator>,
+    mut box_selection: ResMut<BoxSelection>,
+) {
+    let Some(cursor) = windows.get_single().ok().and_then(Window::cursor_position) else {
+        return;
+    };
+
+    if input_mouse.just_pressed(MouseButton::Left) {
+        // Ignoring drags that start over egui panels, manipulator handles or move the camera
+        let pointer_over_egui = contexts.ctx_mut().is_pointer_over_area();
+
+        box_selection.start =
+            (!pointer_over_egui && !manipulator.is_active() && !input.is_navigating())
+                .then_some(cursor);
+        box_selection.current = cursor;
+        box_selection.active = false;
+        return;
+    }
+
+    let Some(start) = box_selection.start else {
+        return;
+    };
+
+    if input_mouse.pressed(MouseButton::Left) {
+        box_selection.current = cursor;
+        if start.distance(cursor) > DRAG_THRESHOLD {
+            box_selection.active = true;
+        }
+    } else if !input_mouse.just_released(MouseButton::Left) {
+        // Drag was finished on the previous frame
+        box_selection.start = None;
+        box_selection.active = false;
+    }
+}
+
+fn select_in_box(
+    box_selection: Res<BoxSelection>,
+    input_mouse: Res<ButtonInput<MouseButton>>,
+    input: EditorInput,
+    cameras: Query<(&Camera, &GlobalTransform), With<PanOrbitCamera>>,
+    pickables: Query<
+        (Entity, &GlobalTransform, Option<&Handle<Mesh>>),
+        (With<Pickable>, Without<EditorOnly>, Without<Deleted>),
+    >,
+    meshes: Res<Assets<Mesh>>,
+    active_selection: Res<ActiveSelection>,
+    locked: LockedEntities,
+    mut history: ResMut<History>,
+) {
+    if !input_mouse.just_released(MouseButton::Left) {
+        return;
+    }
+
+    let Some(rect) = box_selection.rect() else {
+        return;
+    };
+
+    let Ok((camera, camera_transform)) = cameras.get_single() else {
+        return;
+    };
+
+    let add = input.held(InputAction::AddToSelection);
+    let subtract = input.held(InputAction::ToggleSelection);
+
+    let origin = viewport_origin(camera);
+    let mut selection = active_selection.clone();
+
+    if !add && !subtract {
+        selection.clear();
+    }
+
+    for (entity, transform, mesh) in pickables.iter() {
+        if locked.is_locked(entity) {
+            continue;
+        }
+
+        let (half_extents, transform) = extract_scale_and_transform(Some(transform), mesh, &meshes);
+
+        // Entity is inside only when every corner of its bounds is projected inside the box
+        let inside = (0..8).all(|corner| {
+            let sign = Vec3::new(
+                if corner & 1 == 0 { -1. } else { 1. },
+                if corner & 2 == 0 { -1. } else { 1. },
+                if corner & 4 == 0 { -1. } else { 1. },
+            );
+            let point = transform.transform_point(half_extents * sign);
+
+            // Box is in window coordinates, projected points are relative to the viewport
+            camera
+                .world_to_viewport(camera_transform, point)
+                .is_some_and(|point| rect.contains(point + origin))
+        });
+
+        if !inside {
+            continue;
+        }
+
+        if subtract {
+            selection.remove(entity);
+        } else {
+            selection.add(entity);
+        }
+    }
+
+    history.change_selection(&active_selection, |active_selection| {
+        *active_selection = selection;
+    });
+}
+
+fn draw_box_selection(mut contexts: EguiContexts, box_selection: Res<BoxSelection>) {
+    let Some(rect) = box_selection.rect() else {
+        return;
+    };
+
+    let rect = egui::Rect::from_min_max(
+        egui::pos2(rect.min.x, rect.min.y),
+        egui::pos2(rect.max.x, rect.max.y),
+    );
+
+    contexts
+        .ctx_mut()
+        .layer_painter(LayerId::new(Order::Foreground, Id::new("box_selection")))
+        .rect(
+            rect,
+            Rounding::ZERO,
+            Color32::from_rgba_unmultiplied(70, 130, 230, 40),
+            Stroke::new(1., Color32::from_rgb(70, 130, 230)),
+        );
+}
+
+pub struct BoxSelectionPlugin;
+
+impl Plugin for BoxSelectionPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<BoxSelection>().add_systems(
+            Update,
+            (update_box_selection, select_in_box, draw_box_selection)
+                .chain()
+                .after(update_manipulator),
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use bevy::{
+    ecs::{
+        event::{Event, EventReader, EventWriter},
+        system::{Query, ResMut},
+    },
+    log::info,
+    transform::components::Transform,
+};
+use bevy_egui::EguiContexts;
+
+use crate::{
+    input_map::{EditorInput, InputAction, BOOKMARK_KEYS},
+    scene_file::SceneEditorState,
+};
+
+use super::{transition::CameraView, PanOrbitCamera};
+
+/// Numbered camera view, stored in the editor state of the scene
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CameraBookmark {
+    Save(u8),
+    Recall(u8),
+}
+
+pub fn bookmark_shortcuts(
+    mut contexts: EguiContexts,
+    input: EditorInput,
+    mut bookmarks: EventWriter<CameraBookmark>,
+) {
+    if contexts.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+
+    for number in 1..=BOOKMARK_KEYS.len() as u8 {
+        if input.just_pressed(InputAction::SaveBookmark(number)) {
+            bookmarks.send(CameraBookmark::Save(number));
+        } else if input.just_pressed(InputAction::RecallBookmark(number)) {
+            bookmarks.send(CameraBookmark::Recall(number));
+        }
+    }
+}
+
+/**
+ * Recalled bookmark is reached with a transition, same as framing
+ */
+pub fn apply_bookmarks(
+    mut bookmarks: EventReader<CameraBookmark>,
+    mut editor_state: ResMut<SceneEditorState>,
+    mut cameras: Query<(&mut PanOrbitCamera, &Transform)>,
+) {
+    for bookmark in bookmarks.read() {
+        for (mut pan_orbit, transform) in cameras.iter_mut() {
+            match *bookmark {
+                CameraBookmark::Save(number) => {
+                    let view = CameraView::of(&pan_orbit, transform);
+                    editor_state.bookmarks.insert(number, view);
+                    info!("Camera bookmark {number} saved");
+                }
+                CameraBookmark::Recall(number) => {
+                    if let Some(view) = editor_state.bookmarks.get(&number) {
+                        pan_orbit.transition = Some(*view);
+                    }
+                }
+            }
+        }
+    }
+}
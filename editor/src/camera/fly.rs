@@ -0,0 +1,122 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        event::EventReader,
+        system::{Query, Res},
+    },
+    input::mouse::{MouseMotion, MouseWheel},
+    math::{Quat, Vec2, Vec3},
+    time::Time,
+    transform::components::Transform,
+};
+use bevy_egui::EguiContexts;
+
+use crate::input_map::{EditorInput, InputAction};
+
+use super::PanOrbitCamera;
+
+/// Radians of rotation per pixel of mouse movement
+const LOOK_SENSITIVITY: f32 = 0.004;
+
+/// Speed is multiplied by this for every scroll step
+const SPEED_STEP: f32 = 1.2;
+
+const MIN_SPEED: f32 = 0.1;
+const MAX_SPEED: f32 = 1000.;
+
+/// Boost multiplies the speed while held
+const BOOST: f32 = 4.;
+
+/// First-person navigation, that takes over from orbiting while the fly look binding is held
+/// and movement keys are used
+#[derive(Component)]
+pub struct FlyCamera {
+    pub active: bool,
+    /// Units per second
+    pub speed: f32,
+}
+
+impl Default for FlyCamera {
+    fn default() -> Self {
+        FlyCamera {
+            active: false,
+            speed: 5.,
+        }
+    }
+}
+
+/**
+ * Movement keys are checked regardless of modifiers, so boost can be held along with them
+ */
+fn movement(input: &EditorInput) -> Vec3 {
+    [
+        (InputAction::FlyForward, Vec3::NEG_Z),
+        (InputAction::FlyBackward, Vec3::Z),
+        (InputAction::FlyLeft, Vec3::NEG_X),
+        (InputAction::FlyRight, Vec3::X),
+        (InputAction::FlyUp, Vec3::Y),
+        (InputAction::FlyDown, Vec3::NEG_Y),
+    ]
+    .into_iter()
+    .filter(|(action, _)| input.held(*action))
+    .map(|(_, direction)| direction)
+    .sum()
+}
+
+/**
+ * Dragging with the fly look button orbits until one of movement keys is pressed,
+ * from then until the button is released mouse looks around and movement keys move the camera
+ */
+pub fn fly_camera(
+    mut contexts: EguiContexts,
+    time: Res<Time>,
+    input: EditorInput,
+    mut ev_motion: EventReader<MouseMotion>,
+    mut ev_scroll: EventReader<MouseWheel>,
+    mut cameras: Query<(&mut FlyCamera, &mut PanOrbitCamera, &mut Transform)>,
+) {
+    let look = ev_motion.read().map(|ev| ev.delta).sum::<Vec2>();
+    let scroll = ev_scroll.read().map(|ev| ev.y).sum::<f32>();
+
+    let movement = if contexts.ctx_mut().wants_keyboard_input() {
+        Vec3::ZERO
+    } else {
+        movement(&input)
+    };
+    let boost = if input.held(InputAction::FlyBoost) {
+        BOOST
+    } else {
+        1.
+    };
+
+    for (mut fly, mut pan_orbit, mut transform) in cameras.iter_mut() {
+        if !input.held(InputAction::FlyLook) {
+            fly.active = false;
+            continue;
+        }
+
+        if !fly.active {
+            if movement == Vec3::ZERO {
+                continue;
+            }
+            fly.active = true;
+            pan_orbit.transition = None;
+        }
+
+        // Yaw around the world up, pitch around the camera's own right axis
+        let yaw = Quat::from_rotation_y(-look.x * LOOK_SENSITIVITY);
+        let pitch = Quat::from_rotation_x(-look.y * LOOK_SENSITIVITY);
+        transform.rotation = yaw * transform.rotation * pitch;
+
+        fly.speed = (fly.speed * SPEED_STEP.powf(scroll)).clamp(MIN_SPEED, MAX_SPEED);
+
+        // Up and down stay vertical, the rest follows the view
+        let direction = transform.rotation * Vec3::new(movement.x, 0., movement.z)
+            + Vec3::new(0., movement.y, 0.);
+        transform.translation +=
+            direction.normalize_or_zero() * fly.speed * boost * time.delta_seconds();
+
+        // Orbiting continues around the point in front of the camera
+        pan_orbit.focus = transform.translation + transform.forward() * pan_orbit.radius;
+    }
+}
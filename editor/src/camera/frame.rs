@@ -0,0 +1,134 @@
+use bevy::{
+    ecs::{
+        entity::Entity,
+        event::{Event, EventReader, EventWriter},
+        system::{Query, Res},
+    },
+    hierarchy::Children,
+    math::Vec3,
+    render::{camera::Projection, primitives::Aabb},
+    transform::components::{GlobalTransform, Transform},
+};
+use bevy_egui::EguiContexts;
+
+use crate::{
+    active_selection::ActiveSelection,
+    input_map::{EditorInput, InputAction},
+};
+
+use super::{transition::CameraView, views::orthographic_height, PanOrbitCamera};
+
+/// Smallest bounding radius to frame, so points and tiny objects aren't zoomed into
+const MIN_FRAME_RADIUS: f32 = 0.5;
+
+/// Moves the camera, so the selected entities fill the view
+#[derive(Event, Default)]
+pub struct FrameSelection;
+
+pub fn frame_shortcut(
+    mut contexts: EguiContexts,
+    input: EditorInput,
+    mut frame_selection: EventWriter<FrameSelection>,
+) {
+    if contexts.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+
+    if input.just_pressed(InputAction::FrameSelection) {
+        frame_selection.send_default();
+    }
+}
+
+/**
+ * World space bounds of the entities with their descendants.
+ * Entities without a mesh count as points
+ */
+fn selection_bounds(
+    entities: &[Entity],
+    bounds: &Query<(&GlobalTransform, Option<&Aabb>)>,
+    children: &Query<&Children>,
+) -> Option<(Vec3, Vec3)> {
+    let mut to_visit = entities.to_vec();
+    let mut min_max: Option<(Vec3, Vec3)> = None;
+
+    while let Some(entity) = to_visit.pop() {
+        if let Ok(entity_children) = children.get(entity) {
+            to_visit.extend(entity_children.iter());
+        }
+
+        let Ok((transform, aabb)) = bounds.get(entity) else {
+            continue;
+        };
+
+        let points = match aabb {
+            Some(aabb) => (0..8)
+                .map(|corner| {
+                    let sign = Vec3::new(
+                        if corner & 1 == 0 { -1. } else { 1. },
+                        if corner & 2 == 0 { -1. } else { 1. },
+                        if corner & 4 == 0 { -1. } else { 1. },
+                    );
+                    transform.transform_point(
+                        Vec3::from(aabb.center) + Vec3::from(aabb.half_extents) * sign,
+                    )
+                })
+                .collect(),
+            None => vec![transform.translation()],
+        };
+
+        for point in points {
+            min_max = Some(match min_max {
+                Some((min, max)) => (min.min(point), max.max(point)),
+                None => (point, point),
+            });
+        }
+    }
+
+    min_max
+}
+
+/**
+ * Distance from which a sphere of given radius fits into the view
+ */
+fn fit_distance(projection: &Projection, radius: f32) -> f32 {
+    match projection {
+        Projection::Perspective(perspective) => {
+            let half_vertical = perspective.fov * 0.5;
+            let half_horizontal = (half_vertical.tan() * perspective.aspect_ratio).atan();
+
+            radius / half_vertical.min(half_horizontal).sin()
+        }
+        // Orthographic view is as high as what perspective camera sees at the focus
+        Projection::Orthographic(orthographic) => {
+            let aspect_ratio = orthographic.area.width() / orthographic.area.height();
+            radius / (orthographic_height(1.) * 0.5 * aspect_ratio.min(1.))
+        }
+    }
+}
+
+pub fn frame_selection(
+    mut frame_selection: EventReader<FrameSelection>,
+    active_selection: Res<ActiveSelection>,
+    bounds: Query<(&GlobalTransform, Option<&Aabb>)>,
+    children: Query<&Children>,
+    mut cameras: Query<(&mut PanOrbitCamera, &Transform, &Projection)>,
+) {
+    if frame_selection.is_empty() {
+        return;
+    }
+    frame_selection.clear();
+
+    let Some((min, max)) = selection_bounds(active_selection.entities(), &bounds, &children) else {
+        return;
+    };
+
+    let radius = ((max - min) * 0.5).length().max(MIN_FRAME_RADIUS);
+
+    for (mut pan_orbit, transform, projection) in cameras.iter_mut() {
+        pan_orbit.transition = Some(CameraView {
+            focus: (min + max) * 0.5,
+            radius: fit_distance(projection, radius),
+            rotation: transform.rotation,
+        });
+    }
+}
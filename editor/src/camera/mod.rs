@@ -1,29 +1,51 @@
+mod bookmarks;
+mod fly;
+mod frame;
+mod transition;
+mod viewport;
+mod views;
+
 use bevy::{
-    app::{Plugin, Startup, Update},
+    app::{Plugin, PostUpdate, Startup, Update},
+    core::Name,
     core_pipeline::core_3d::Camera3dBundle,
     ecs::{
         component::Component,
         event::EventReader,
         query::With,
-        system::{Commands, Query, Res, Resource},
-    },
-    input::{
-        mouse::{MouseButton, MouseMotion, MouseWheel},
-        ButtonInput,
+        schedule::IntoSystemConfigs,
+        system::{Commands, Local, Query, Res},
     },
+    input::mouse::{MouseMotion, MouseWheel},
     math::{Mat3, Quat, Vec2, Vec3},
-    prelude::{Deref, DerefMut},
-    render::camera::Projection,
+    render::camera::{Camera, CameraUpdateSystem, Projection},
     transform::components::Transform,
     window::{PrimaryWindow, Window},
 };
+use common::editor_components::EditorOnly;
+
+use crate::{
+    input_map::{EditorInput, InputAction},
+    manipulator::Manipulator,
+};
+
+pub use self::{
+    bookmarks::CameraBookmark,
+    fly::FlyCamera,
+    frame::FrameSelection,
+    transition::CameraView,
+    viewport::{viewport_cursor, viewport_origin},
+    views::{AxisView, ChangeView},
+};
 
 #[derive(Component)]
-struct PanOrbitCamera {
+pub struct PanOrbitCamera {
     /// The "focus point" to orbit around. It is automatically updated when panning the camera
     pub focus: Vec3,
     pub radius: f32,
     pub upside_down: bool,
+    /// View the camera is smoothly moving to, manual input cancels it
+    pub transition: Option<CameraView>,
 }
 
 impl Default for PanOrbitCamera {
@@ -32,50 +54,67 @@ impl Default for PanOrbitCamera {
             focus: Vec3::ZERO,
             radius: 5.0,
             upside_down: false,
+            transition: None,
         }
     }
 }
 
-fn get_primary_window_size(window: &Window) -> Vec2 {
-    Vec2::new(window.width(), window.height())
+/**
+ * Size of the area the camera renders into, mouse movement is relative to it
+ */
+fn viewport_size(camera: &Camera, window: &Window) -> Vec2 {
+    camera
+        .logical_viewport_size()
+        .unwrap_or(Vec2::new(window.width(), window.height()))
 }
 
 fn pan_orbit_camera(
     windows: Query<&Window, With<PrimaryWindow>>,
     mut ev_motion: EventReader<MouseMotion>,
     mut ev_scroll: EventReader<MouseWheel>,
-    input_mouse: Res<ButtonInput<MouseButton>>,
-    mut query: Query<(&mut PanOrbitCamera, &mut Transform, &Projection)>,
+    input: EditorInput,
+    manipulator: Res<Manipulator>,
+    mut was_orbiting: Local<bool>,
+    mut query: Query<(
+        &mut PanOrbitCamera,
+        &mut Transform,
+        &Camera,
+        &Projection,
+        &FlyCamera,
+    )>,
 ) {
-    // change input mapping for orbit and panning here
-    let orbit_button = MouseButton::Right;
-    let pan_button = MouseButton::Middle;
-
     let window = windows.get_single().unwrap();
 
     let mut pan = Vec2::ZERO;
     let mut rotation_move = Vec2::ZERO;
     let mut scroll = 0.0;
-    let mut orbit_button_changed = false;
+    // Bindings shared with the manipulator, like LMB, move only the dragged handle
+    let dragging_handle = manipulator.is_dragging();
+    let orbiting = !dragging_handle && input.pressed(InputAction::Orbit);
 
-    if input_mouse.pressed(orbit_button) {
+    if orbiting {
         for ev in ev_motion.read() {
             rotation_move += ev.delta;
         }
-    } else if input_mouse.pressed(pan_button) {
+    } else if !dragging_handle && input.pressed(InputAction::Pan) {
         // Pan only if we're not rotating at the moment
         for ev in ev_motion.read() {
             pan += ev.delta;
         }
     }
     for ev in ev_scroll.read() {
-        scroll += ev.y;
-    }
-    if input_mouse.just_released(orbit_button) || input_mouse.just_pressed(orbit_button) {
-        orbit_button_changed = true;
+        if !dragging_handle {
+            scroll += ev.y;
+        }
     }
+    let orbit_button_changed = orbiting != *was_orbiting;
+    *was_orbiting = orbiting;
+
+    for (mut pan_orbit, mut transform, camera, projection, fly) in query.iter_mut() {
+        if fly.active {
+            continue;
+        }
 
-    for (mut pan_orbit, mut transform, projection) in query.iter_mut() {
         if orbit_button_changed {
             // only check for upside down when orbiting started or ended this frame
             // if the camera is "upside" down, panning horizontally would be inverted, so invert the input to make it correct
@@ -86,7 +125,7 @@ fn pan_orbit_camera(
         let mut any = false;
         if rotation_move.length_squared() > 0.0 {
             any = true;
-            let window = get_primary_window_size(window);
+            let window = viewport_size(camera, window);
             let delta_x = {
                 let delta = rotation_move.x / window.x * std::f32::consts::PI * 2.0;
                 if pan_orbit.upside_down {
@@ -103,9 +142,16 @@ fn pan_orbit_camera(
         } else if pan.length_squared() > 0.0 {
             any = true;
             // make panning distance independent of resolution and FOV,
-            let window = get_primary_window_size(window);
-            if let Projection::Perspective(projection) = projection {
-                pan *= Vec2::new(projection.fov * projection.aspect_ratio, projection.fov) / window;
+            let window = viewport_size(camera, window);
+            match projection {
+                Projection::Perspective(projection) => {
+                    pan *= Vec2::new(projection.fov * projection.aspect_ratio, projection.fov)
+                        / window;
+                }
+                // Area is in world units already, while translation is scaled by radius below
+                Projection::Orthographic(projection) => {
+                    pan *= projection.area.size() / window / pan_orbit.radius;
+                }
             }
             // translate by local axes
             let right = transform.rotation * Vec3::X * -pan.x;
@@ -121,6 +167,8 @@ fn pan_orbit_camera(
         }
 
         if any {
+            pan_orbit.transition = None;
+
             // emulating parent/child to make the yaw/y-axis rotation behave like a turntable
             // parent = x and y rotation
             // child = z-offset
@@ -137,66 +185,54 @@ fn pan_orbit_camera(
 
 const CAMERA_TARGET: Vec3 = Vec3::ZERO;
 
-#[derive(Resource, Deref, DerefMut)]
-struct OriginalCameraTransform(Transform);
-
 fn spawn_camera(mut commands: Commands) {
     let camera_pos = Vec3::new(-2.0, 2.5, 5.0);
-    let camera_transform =
-        Transform::from_translation(camera_pos).looking_at(CAMERA_TARGET, Vec3::Y);
-    commands.insert_resource(OriginalCameraTransform(camera_transform));
 
     let radius = camera_pos.length();
 
     commands.spawn((
         Camera3dBundle {
-            transform: Transform::from_translation(camera_pos).looking_at(Vec3::ZERO, Vec3::Y),
+            transform: Transform::from_translation(camera_pos).looking_at(CAMERA_TARGET, Vec3::Y),
             ..Default::default()
         },
         PanOrbitCamera {
             radius,
             ..Default::default()
         },
+        FlyCamera::default(),
+        Name::new("Editor Camera"),
+        EditorOnly,
     ));
 }
 
-// fn update_camera_transform_system(
-//     occupied_screen_space: Res<OccupiedScreenSpace>,
-//     original_camera_transform: Res<OriginalCameraTransform>,
-//     windows: Query<&Window, With<PrimaryWindow>>,
-//     mut camera_query: Query<(&Projection, &mut Transform)>,
-// ) {
-//     let (camera_projection, mut transform) = match camera_query.get_single_mut() {
-//         Ok((Projection::Perspective(projection), transform)) => (projection, transform),
-//         _ => unreachable!(),
-//     };
-
-//     let distance_to_target = (CAMERA_TARGET - original_camera_transform.translation).length();
-//     let frustum_height = 2.0 * distance_to_target * (camera_projection.fov * 0.5).tan();
-//     let frustum_width = frustum_height * camera_projection.aspect_ratio;
-
-//     let window = windows.single();
-
-//     let left_taken = occupied_screen_space.left / window.width();
-//     let right_taken = occupied_screen_space.right / window.width();
-//     let top_taken = occupied_screen_space.top / window.height();
-//     let bottom_taken = occupied_screen_space.bottom / window.height();
-
-//     let t = *transform;
-//     transform.translation = t.translation
-//         + t.rotation.mul_vec3(Vec3::new(
-//             (right_taken - left_taken) * frustum_width * 0.5,
-//             (top_taken - bottom_taken) * frustum_height * 0.5,
-//             0.0,
-//         ));
-// }
-
 pub struct EditorCameraPlugin;
 
 impl Plugin for EditorCameraPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_systems(Startup, spawn_camera)
-            // .add_systems(Update, update_camera_transform_system)
-            .add_systems(Update, pan_orbit_camera);
+        app.add_event::<FrameSelection>()
+            .add_event::<ChangeView>()
+            .add_event::<CameraBookmark>()
+            .add_systems(Startup, spawn_camera)
+            .add_systems(
+                Update,
+                (
+                    frame::frame_shortcut,
+                    views::view_shortcuts,
+                    bookmarks::bookmark_shortcuts,
+                    frame::frame_selection,
+                    views::change_view,
+                    bookmarks::apply_bookmarks,
+                    fly::fly_camera,
+                    pan_orbit_camera,
+                    transition::animate_transition,
+                    views::sync_orthographic_scale,
+                )
+                    .chain(),
+            )
+            // Projection is recomputed from the viewport right after
+            .add_systems(
+                PostUpdate,
+                viewport::update_viewport.before(CameraUpdateSystem),
+            );
     }
 }
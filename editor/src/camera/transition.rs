@@ -0,0 +1,85 @@
+use bevy::{
+    ecs::system::{Query, Res},
+    math::{Quat, Vec3},
+    time::Time,
+    transform::components::Transform,
+};
+use serde::{Deserialize, Serialize};
+
+use super::PanOrbitCamera;
+
+/// Share of the remaining way covered each second, higher is faster
+const TRANSITION_SPEED: f32 = 10.;
+
+/// Transition ends once the camera is closer than this to the view, relative to its radius
+const TRANSITION_EPSILON: f32 = 0.001;
+
+/// Where the orbit camera looks from
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct CameraView {
+    pub focus: Vec3,
+    pub radius: f32,
+    pub rotation: Quat,
+}
+
+impl CameraView {
+    pub fn of(pan_orbit: &PanOrbitCamera, transform: &Transform) -> Self {
+        CameraView {
+            focus: pan_orbit.focus,
+            radius: pan_orbit.radius,
+            rotation: transform.rotation,
+        }
+    }
+
+    /**
+     * Camera is placed at the radius from the focus, looking at it
+     */
+    pub fn translation(&self) -> Vec3 {
+        self.focus + self.rotation * Vec3::new(0., 0., self.radius)
+    }
+
+    fn lerp(&self, target: &CameraView, t: f32) -> Self {
+        CameraView {
+            focus: self.focus.lerp(target.focus, t),
+            radius: self.radius + (target.radius - self.radius) * t,
+            rotation: self.rotation.slerp(target.rotation, t),
+        }
+    }
+
+    fn is_close_to(&self, target: &CameraView) -> bool {
+        let epsilon = target.radius * TRANSITION_EPSILON;
+
+        self.focus.distance(target.focus) < epsilon
+            && (self.radius - target.radius).abs() < epsilon
+            && self.rotation.angle_between(target.rotation) < TRANSITION_EPSILON
+    }
+}
+
+/**
+ * Moves cameras towards their transition view, slowing down on approach
+ */
+pub fn animate_transition(
+    time: Res<Time>,
+    mut cameras: Query<(&mut PanOrbitCamera, &mut Transform)>,
+) {
+    let t = 1. - (-TRANSITION_SPEED * time.delta_seconds()).exp();
+
+    for (mut pan_orbit, mut transform) in cameras.iter_mut() {
+        let Some(target) = pan_orbit.transition else {
+            continue;
+        };
+
+        let current = CameraView::of(&pan_orbit, &transform);
+        let view = if current.is_close_to(&target) {
+            pan_orbit.transition = None;
+            target
+        } else {
+            current.lerp(&target, t)
+        };
+
+        pan_orbit.focus = view.focus;
+        pan_orbit.radius = view.radius;
+        transform.rotation = view.rotation;
+        transform.translation = view.translation();
+    }
+}
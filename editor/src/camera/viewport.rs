@@ -0,0 +1,83 @@
+use bevy::{
+    ecs::{
+        query::With,
+        system::{Query, Res},
+    },
+    math::{UVec2, Vec2},
+    render::camera::{Camera, Viewport},
+    window::{PrimaryWindow, Window},
+};
+use bevy_egui::EguiSettings;
+
+use crate::ui::editor_screen_space::OccupiedScreenSpace;
+
+use super::PanOrbitCamera;
+
+/**
+ * Top left corner of the viewport in logical window coordinates
+ */
+pub fn viewport_origin(camera: &Camera) -> Vec2 {
+    camera
+        .logical_viewport_rect()
+        .map_or(Vec2::ZERO, |rect| rect.min)
+}
+
+/**
+ * Cursor relative to the viewport, which is what camera projections expect.
+ * It isn't clamped, so drags can continue over the panels
+ */
+pub fn viewport_cursor(camera: &Camera, window: &Window) -> Option<Vec2> {
+    window
+        .cursor_position()
+        .map(|cursor| cursor - viewport_origin(camera))
+}
+
+/**
+ * Scene is rendered only into the area, that egui panels leave free
+ */
+pub fn update_viewport(
+    occupied_screen_space: Res<OccupiedScreenSpace>,
+    egui_settings: Res<EguiSettings>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<&mut Camera, With<PanOrbitCamera>>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+
+    let window_size = UVec2::new(window.physical_width(), window.physical_height());
+    // Minimized window has nothing to render into
+    if window_size.cmpeq(UVec2::ZERO).any() {
+        return;
+    }
+
+    // Panels are measured in egui points
+    let scale = egui_settings.scale_factor * window.scale_factor();
+    let physical = |points: f32| (points * scale).round() as u32;
+
+    let start = UVec2::new(
+        physical(occupied_screen_space.left),
+        physical(occupied_screen_space.top),
+    )
+    .min(window_size - UVec2::ONE);
+    let end = window_size.saturating_sub(UVec2::new(
+        physical(occupied_screen_space.right),
+        physical(occupied_screen_space.bottom),
+    ));
+    // Viewport can't be empty, even when panels cover the whole window
+    let size = end.saturating_sub(start).max(UVec2::ONE);
+
+    for mut camera in cameras.iter_mut() {
+        if camera.viewport.as_ref().is_some_and(|viewport| {
+            viewport.physical_position == start && viewport.physical_size == size
+        }) {
+            continue;
+        }
+
+        camera.viewport = Some(Viewport {
+            physical_position: start,
+            physical_size: size,
+            ..Default::default()
+        });
+    }
+}
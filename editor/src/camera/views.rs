@@ -0,0 +1,168 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use bevy::{
+    ecs::{
+        event::{Event, EventReader, EventWriter},
+        system::Query,
+    },
+    math::Quat,
+    render::camera::{OrthographicProjection, PerspectiveProjection, Projection, ScalingMode},
+    transform::components::Transform,
+};
+use bevy_egui::EguiContexts;
+
+use crate::input_map::{EditorInput, InputAction};
+
+use super::{transition::CameraView, PanOrbitCamera};
+
+/// Orthographic camera sees this far in front of and behind itself
+const ORTHOGRAPHIC_DEPTH: f32 = 1000.;
+
+/// Views along the world axes, named after the side camera looks from
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AxisView {
+    Top,
+    Bottom,
+    Front,
+    Back,
+    Left,
+    Right,
+}
+
+impl AxisView {
+    pub const ALL: [AxisView; 6] = [
+        AxisView::Top,
+        AxisView::Bottom,
+        AxisView::Front,
+        AxisView::Back,
+        AxisView::Left,
+        AxisView::Right,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AxisView::Top => "Top",
+            AxisView::Bottom => "Bottom",
+            AxisView::Front => "Front",
+            AxisView::Back => "Back",
+            AxisView::Left => "Left",
+            AxisView::Right => "Right",
+        }
+    }
+
+    pub fn action(&self) -> InputAction {
+        match self {
+            AxisView::Top => InputAction::ViewTop,
+            AxisView::Bottom => InputAction::ViewBottom,
+            AxisView::Front => InputAction::ViewFront,
+            AxisView::Back => InputAction::ViewBack,
+            AxisView::Left => InputAction::ViewLeft,
+            AxisView::Right => InputAction::ViewRight,
+        }
+    }
+
+    /// Camera looks along its local -Z, so front view has no rotation
+    fn rotation(&self) -> Quat {
+        match self {
+            AxisView::Top => Quat::from_rotation_x(-FRAC_PI_2),
+            AxisView::Bottom => Quat::from_rotation_x(FRAC_PI_2),
+            AxisView::Front => Quat::IDENTITY,
+            AxisView::Back => Quat::from_rotation_y(PI),
+            AxisView::Left => Quat::from_rotation_y(-FRAC_PI_2),
+            AxisView::Right => Quat::from_rotation_y(FRAC_PI_2),
+        }
+    }
+}
+
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChangeView {
+    /// Orthographic view along one of the axes
+    Axis(AxisView),
+    TogglePerspective,
+}
+
+/**
+ * Height of the orthographic view, which matches what perspective camera sees at the focus
+ */
+pub fn orthographic_height(radius: f32) -> f32 {
+    2. * radius * (PerspectiveProjection::default().fov * 0.5).tan()
+}
+
+fn orthographic(radius: f32) -> Projection {
+    Projection::Orthographic(OrthographicProjection {
+        scale: orthographic_height(radius),
+        near: -ORTHOGRAPHIC_DEPTH,
+        far: ORTHOGRAPHIC_DEPTH,
+        scaling_mode: ScalingMode::FixedVertical(1.),
+        ..Default::default()
+    })
+}
+
+pub fn view_shortcuts(
+    mut contexts: EguiContexts,
+    input: EditorInput,
+    mut change_view: EventWriter<ChangeView>,
+) {
+    if contexts.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+
+    if let Some(view) = AxisView::ALL
+        .into_iter()
+        .find(|view| input.just_pressed(view.action()))
+    {
+        change_view.send(ChangeView::Axis(view));
+    } else if input.just_pressed(InputAction::ToggleProjection) {
+        change_view.send(ChangeView::TogglePerspective);
+    }
+}
+
+pub fn change_view(
+    mut change_view: EventReader<ChangeView>,
+    mut cameras: Query<(&mut PanOrbitCamera, &Transform, &mut Projection)>,
+) {
+    for change in change_view.read() {
+        for (mut pan_orbit, transform, mut projection) in cameras.iter_mut() {
+            match change {
+                ChangeView::Axis(view) => {
+                    let current = pan_orbit
+                        .transition
+                        .unwrap_or(CameraView::of(&pan_orbit, transform));
+                    pan_orbit.transition = Some(CameraView {
+                        rotation: view.rotation(),
+                        ..current
+                    });
+
+                    if let Projection::Perspective(_) = *projection {
+                        *projection = orthographic(pan_orbit.radius);
+                    }
+                }
+                ChangeView::TogglePerspective => {
+                    *projection = match *projection {
+                        Projection::Perspective(_) => orthographic(pan_orbit.radius),
+                        Projection::Orthographic(_) => {
+                            Projection::Perspective(PerspectiveProjection::default())
+                        }
+                    };
+                }
+            }
+        }
+    }
+}
+
+/**
+ * Orthographic camera zooms by scale instead of distance, so it follows the orbit radius
+ */
+pub fn sync_orthographic_scale(mut cameras: Query<(&PanOrbitCamera, &mut Projection)>) {
+    for (pan_orbit, mut projection) in cameras.iter_mut() {
+        let scale = orthographic_height(pan_orbit.radius);
+
+        // Changing projection makes camera recompute it, so it's only touched when needed
+        if matches!(*projection, Projection::Orthographic(ref orthographic) if orthographic.scale != scale)
+        {
+            if let Projection::Orthographic(orthographic) = projection.as_mut() {
+                orthographic.scale = scale;
+            }
+        }
+    }
+}
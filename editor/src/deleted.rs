@@ -0,0 +1,113 @@
+use bevy::{
+    ecs::{component::Component, entity::Entity, world::World},
+    hierarchy::{BuildWorldChildren, Children, DespawnRecursiveExt, Parent},
+    render::view::Visibility,
+};
+
+/// Entity deleted in the editor, along with its descendants. It's kept detached and hidden
+/// instead of being despawned, so undo brings back the same id and commands keep working
+#[derive(Component)]
+pub struct Deleted;
+
+/// Where the deleted entity was, to put it back on undo
+pub struct DeletedEntity {
+    pub entity: Entity,
+    parent: Option<Entity>,
+    /// Position among children of the parent
+    index: usize,
+    visibility: Option<Visibility>,
+}
+
+/**
+ * Entity followed by all of its descendants
+ */
+fn with_descendants(world: &World, entity: Entity) -> Vec<Entity> {
+    let mut entities = vec![entity];
+    let mut index_to_visit = 0;
+
+    while let Some(visited) = entities.get(index_to_visit) {
+        if let Some(children) = world.get::<Children>(*visited) {
+            entities.extend(children.iter());
+        }
+        index_to_visit += 1;
+    }
+
+    entities
+}
+
+/**
+ * Detaches and hides the entity with its descendants.
+ * None if it doesn't exist or is deleted already
+ */
+pub fn delete(world: &mut World, entity: Entity) -> Option<DeletedEntity> {
+    if world.get_entity(entity).is_none() || world.get::<Deleted>(entity).is_some() {
+        return None;
+    }
+
+    let parent = world.get::<Parent>(entity).map(Parent::get);
+    let index = parent
+        .and_then(|parent| world.get::<Children>(parent))
+        .and_then(|children| children.iter().position(|child| *child == entity))
+        .unwrap_or_default();
+    let visibility = world.get::<Visibility>(entity).copied();
+
+    for entity in with_descendants(world, entity) {
+        world.entity_mut(entity).insert(Deleted);
+    }
+
+    let mut entity_mut = world.entity_mut(entity);
+    entity_mut.remove_parent();
+    if visibility.is_some() {
+        entity_mut.insert(Visibility::Hidden);
+    }
+
+    Some(DeletedEntity {
+        entity,
+        parent,
+        index,
+        visibility,
+    })
+}
+
+/**
+ * Puts the entity back where it was, with the visibility it had
+ */
+pub fn restore(world: &mut World, deleted: &DeletedEntity) {
+    let entity = deleted.entity;
+    if world.get_entity(entity).is_none() {
+        return;
+    }
+
+    for entity in with_descendants(world, entity) {
+        world.entity_mut(entity).remove::<Deleted>();
+    }
+
+    if let Some(visibility) = deleted.visibility {
+        world.entity_mut(entity).insert(visibility);
+    }
+
+    if let Some(parent) = deleted
+        .parent
+        .filter(|parent| world.get_entity(*parent).is_some())
+    {
+        let len = world
+            .get::<Children>(parent)
+            .map_or(0, |children| children.len());
+        world
+            .entity_mut(parent)
+            .insert_children(deleted.index.min(len), &[entity]);
+    }
+}
+
+/**
+ * Despawns deleted entity for good, once no command can restore it anymore
+ */
+pub fn purge(world: &mut World, deleted: &DeletedEntity) {
+    if world.get::<Deleted>(deleted.entity).is_none() {
+        return;
+    }
+
+    if let Some(entity) = world.get_entity_mut(deleted.entity) {
+        entity.despawn_recursive();
+    }
+}
@@ -0,0 +1,808 @@
+use std::any::TypeId;
+
+use bevy::{
+    app::{Plugin, PostUpdate, Update},
+    core::Name,
+    ecs::{
+        entity::Entity,
+        reflect::{AppTypeRegistry, ReflectComponent},
+        schedule::IntoSystemConfigs,
+        system::{Res, ResMut, Resource},
+        world::World,
+    },
+    hierarchy::BuildWorldChildren,
+    input::{keyboard::KeyCode, mouse::MouseButton, ButtonInput},
+    reflect::Reflect,
+    render::prelude::SpatialBundle,
+    transform::{components::Transform, TransformSystem},
+};
+use bevy_egui::EguiContexts;
+use bevy_mod_picking::PickableBundle;
+use common::editor_components::RootOrder;
+
+use crate::{
+    active_selection::ActiveSelection,
+    deleted::{self, DeletedEntity},
+    input_map::{EditorInput, InputAction},
+    snapshot::EntitySnapshot,
+};
+
+/// Every change made by the editor to the world goes through one of these,
+/// so it can be undone and redone
+pub enum EditorCommand {
+    SetTransform {
+        /// Entity, transform before and after the change
+        changes: Vec<(Entity, Transform, Transform)>,
+    },
+    SetSelection {
+        before: ActiveSelection,
+        after: ActiveSelection,
+    },
+    /// Any component registered for reflection, edited through the inspector
+    SetComponent {
+        entity: Entity,
+        type_id: TypeId,
+        /// Short type name, used for the history entry
+        name: String,
+        before: Box<dyn Reflect>,
+        after: Box<dyn Reflect>,
+    },
+    AddComponents {
+        entity: Entity,
+        /// Type, short type name and value of each component
+        components: Vec<(TypeId, String, Box<dyn Reflect>)>,
+    },
+    RemoveComponents {
+        entity: Entity,
+        /// Type, short type name and value of each component, to restore them on undo
+        components: Vec<(TypeId, String, Box<dyn Reflect>)>,
+    },
+    SetParent {
+        before: Box<HierarchyState>,
+        after: Box<HierarchyState>,
+    },
+    Spawn {
+        source: SpawnSource,
+        /// Spawned entities, filled when the command is applied for the first time
+        spawned: Vec<Entity>,
+        /// Spawned entities deleted by undo, redo brings them back
+        deleted: Vec<DeletedEntity>,
+        /// Selection to go back to, spawned entities become selected
+        selection: ActiveSelection,
+    },
+    /// Deletes entities with their descendants. They are only hidden, so undo keeps their ids
+    Despawn {
+        entities: Vec<Entity>,
+        /// Deleted entities, taken every time the command is applied
+        deleted: Vec<DeletedEntity>,
+        selection: ActiveSelection,
+    },
+}
+
+pub enum SpawnSource {
+    Empty {
+        parent: Option<Entity>,
+    },
+    /// Deep copies of the entities
+    Duplicate(Vec<Entity>),
+}
+
+/// Parents, order of children and local transforms of the entities moved in the hierarchy
+#[derive(Default)]
+pub struct HierarchyState {
+    /// Moved entity and its parent
+    pub parents: Vec<(Entity, Option<Entity>)>,
+    /// Parent and order of its children, for every parent moved entities were taken from or put to
+    pub children: Vec<(Entity, Vec<Entity>)>,
+    /// Local transforms of moved entities, which keep their world transform
+    pub transforms: Vec<(Entity, Transform)>,
+    /// Order of the roots, that was changed. None if root had no order yet
+    pub root_orders: Vec<(Entity, Option<RootOrder>)>,
+}
+
+impl HierarchyState {
+    fn restore(&self, world: &mut World) {
+        for (entity, parent) in &self.parents {
+            if world.get_entity(*entity).is_none() {
+                continue;
+            }
+
+            match parent.filter(|parent| world.get_entity(*parent).is_some()) {
+                Some(parent) => {
+                    world.entity_mut(parent).add_child(*entity);
+                }
+                None => {
+                    world.entity_mut(*entity).remove_parent();
+                }
+            }
+        }
+
+        // Inserting every child at once puts them into the stored order
+        for (parent, children) in &self.children {
+            if children.is_empty() || world.get_entity(*parent).is_none() {
+                continue;
+            }
+
+            world.entity_mut(*parent).insert_children(0, children);
+        }
+
+        for (entity, transform) in &self.transforms {
+            set_transform(world, *entity, *transform);
+        }
+
+        for (entity, order) in &self.root_orders {
+            let Some(mut entity) = world.get_entity_mut(*entity) else {
+                continue;
+            };
+
+            match order {
+                Some(order) => {
+                    entity.insert(*order);
+                }
+                None => {
+                    entity.remove::<RootOrder>();
+                }
+            }
+        }
+    }
+}
+
+impl EditorCommand {
+    pub fn name(&self) -> String {
+        match self {
+            EditorCommand::SetTransform { changes } if changes.len() == 1 => {
+                format!("Transform {:?}", changes[0].0)
+            }
+            EditorCommand::SetTransform { changes } => {
+                format!("Transform {} entities", changes.len())
+            }
+            EditorCommand::SetSelection { after, .. } if after.entities().is_empty() => {
+                "Clear selection".to_owned()
+            }
+            EditorCommand::SetSelection { after, .. } => {
+                format!("Select {} entities", after.entities().len())
+            }
+            EditorCommand::SetComponent { entity, name, .. } => {
+                format!("Edit {name} of {entity:?}")
+            }
+            EditorCommand::AddComponents { entity, components } => {
+                format!("Add {} to {entity:?}", component_names(components))
+            }
+            EditorCommand::RemoveComponents { entity, components } => {
+                format!("Remove {} from {entity:?}", component_names(components))
+            }
+            EditorCommand::SetParent { after, .. } if after.parents.len() == 1 => {
+                format!("Move {:?}", after.parents[0].0)
+            }
+            EditorCommand::SetParent { after, .. } => {
+                format!("Move {} entities", after.parents.len())
+            }
+            EditorCommand::Spawn {
+                source: SpawnSource::Empty { .. },
+                ..
+            } => "Create empty".to_owned(),
+            EditorCommand::Spawn {
+                source: SpawnSource::Duplicate(entities),
+                ..
+            } => format!("Duplicate {} entities", entities.len()),
+            EditorCommand::Despawn { entities, .. } => {
+                format!("Delete {} entities", entities.len())
+            }
+        }
+    }
+
+    fn apply(&mut self, world: &mut World) {
+        match self {
+            EditorCommand::SetTransform { changes } => {
+                for (entity, _, after) in changes {
+                    set_transform(world, *entity, *after);
+                }
+            }
+            EditorCommand::SetSelection { after, .. } => {
+                *world.resource_mut::<ActiveSelection>() = after.clone();
+            }
+            EditorCommand::SetComponent {
+                entity,
+                type_id,
+                after,
+                ..
+            } => {
+                insert_component(world, *entity, *type_id, after.as_ref());
+            }
+            EditorCommand::AddComponents { entity, components } => {
+                for (type_id, _, value) in components {
+                    insert_component(world, *entity, *type_id, value.as_ref());
+                }
+            }
+            EditorCommand::RemoveComponents { entity, components } => {
+                for (type_id, _, _) in components {
+                    remove_component(world, *entity, *type_id);
+                }
+            }
+            EditorCommand::SetParent { after, .. } => after.restore(world),
+            EditorCommand::Spawn {
+                source,
+                spawned,
+                deleted,
+                selection,
+            } => {
+                *selection = world.resource::<ActiveSelection>().clone();
+
+                // Redo brings back the same entities, so later commands can refer to them
+                if spawned.is_empty() {
+                    *spawned = source.spawn(world);
+                } else {
+                    restore_deleted(world, deleted);
+                }
+
+                let mut active_selection = world.resource_mut::<ActiveSelection>();
+                active_selection.clear();
+                for entity in spawned.iter() {
+                    active_selection.add(*entity);
+                }
+            }
+            EditorCommand::Despawn {
+                entities,
+                deleted,
+                selection,
+            } => {
+                *selection = world.resource::<ActiveSelection>().clone();
+
+                *deleted = entities
+                    .iter()
+                    .filter_map(|entity| deleted::delete(world, *entity))
+                    .collect();
+            }
+        }
+    }
+
+    fn undo(&mut self, world: &mut World) {
+        match self {
+            EditorCommand::SetTransform { changes } => {
+                for (entity, before, _) in changes {
+                    set_transform(world, *entity, *before);
+                }
+            }
+            EditorCommand::SetSelection { before, .. } => {
+                *world.resource_mut::<ActiveSelection>() = before.clone();
+            }
+            EditorCommand::SetComponent {
+                entity,
+                type_id,
+                before,
+                ..
+            } => {
+                insert_component(world, *entity, *type_id, before.as_ref());
+            }
+            EditorCommand::AddComponents { entity, components } => {
+                for (type_id, _, _) in components {
+                    remove_component(world, *entity, *type_id);
+                }
+            }
+            EditorCommand::RemoveComponents { entity, components } => {
+                for (type_id, _, value) in components {
+                    insert_component(world, *entity, *type_id, value.as_ref());
+                }
+            }
+            EditorCommand::SetParent { before, .. } => before.restore(world),
+            EditorCommand::Spawn {
+                spawned,
+                deleted,
+                selection,
+                ..
+            } => {
+                *deleted = spawned
+                    .iter()
+                    .filter_map(|entity| deleted::delete(world, *entity))
+                    .collect();
+
+                *world.resource_mut::<ActiveSelection>() = selection.clone();
+            }
+            EditorCommand::Despawn {
+                deleted, selection, ..
+            } => {
+                restore_deleted(world, deleted);
+
+                *world.resource_mut::<ActiveSelection>() = selection.clone();
+            }
+        }
+    }
+
+    /// Command can't be redone anymore, so entities only it could bring back are despawned
+    fn forget(self, world: &mut World) {
+        if let EditorCommand::Spawn { deleted, .. } = self {
+            for deleted in &deleted {
+                deleted::purge(world, deleted);
+            }
+        }
+    }
+
+    /// Merges later change into this one, keeping the original state from before both of them.
+    /// Returns the command back if they can't be merged
+    fn merge(&mut self, other: EditorCommand) -> Result<(), EditorCommand> {
+        match (self, other) {
+            (
+                EditorCommand::SetTransform { changes },
+                EditorCommand::SetTransform {
+                    changes: other_changes,
+                },
+            ) => {
+                for (entity, before, after) in other_changes {
+                    match changes
+                        .iter_mut()
+                        .find(|(changed, _, _)| *changed == entity)
+                    {
+                        Some(change) => change.2 = after,
+                        None => changes.push((entity, before, after)),
+                    }
+                }
+                Ok(())
+            }
+            (
+                EditorCommand::SetComponent {
+                    entity,
+                    type_id,
+                    after,
+                    ..
+                },
+                EditorCommand::SetComponent {
+                    entity: other_entity,
+                    type_id: other_type_id,
+                    after: other_after,
+                    ..
+                },
+            ) if *entity == other_entity && *type_id == other_type_id => {
+                *after = other_after;
+                Ok(())
+            }
+            (_, other) => Err(other),
+        }
+    }
+}
+
+/**
+ * Restores in the reverse order of deletion, so every entity gets back its position among siblings
+ */
+fn restore_deleted(world: &mut World, deleted: &[DeletedEntity]) {
+    for deleted in deleted.iter().rev() {
+        deleted::restore(world, deleted);
+    }
+}
+
+fn set_transform(world: &mut World, entity: Entity, transform: Transform) {
+    if let Some(mut current) = world.get_mut::<Transform>(entity) {
+        *current = transform;
+    }
+}
+
+impl SpawnSource {
+    /**
+     * Returns root entities that were spawned
+     */
+    fn spawn(&self, world: &mut World) -> Vec<Entity> {
+        match self {
+            SpawnSource::Empty { parent } => {
+                let entity = world
+                    .spawn((
+                        SpatialBundle::default(),
+                        Name::new("Empty"),
+                        PickableBundle::default(),
+                    ))
+                    .id();
+
+                if let Some(parent) = parent.filter(|parent| world.get_entity(*parent).is_some()) {
+                    world.entity_mut(parent).add_child(entity);
+                }
+
+                vec![entity]
+            }
+            SpawnSource::Duplicate(entities) => {
+                let snapshots = entities
+                    .iter()
+                    .filter(|entity| world.get_entity(**entity).is_some())
+                    .map(|entity| EntitySnapshot::take(world, *entity))
+                    .collect::<Vec<_>>();
+
+                snapshots
+                    .iter()
+                    .map(|snapshot| snapshot.spawn_copy(world))
+                    .collect()
+            }
+        }
+    }
+}
+
+fn component_names(components: &[(TypeId, String, Box<dyn Reflect>)]) -> String {
+    components
+        .iter()
+        .map(|(_, name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/**
+ * Replaces the whole component, so removed list items or switched enum variants are restored too
+ */
+fn insert_component(world: &mut World, entity: Entity, type_id: TypeId, value: &dyn Reflect) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let Some(reflect_component) = type_registry.get_type_data::<ReflectComponent>(type_id) else {
+        return;
+    };
+
+    if let Some(mut entity) = world.get_entity_mut(entity) {
+        reflect_component.insert(&mut entity, value, &type_registry);
+    }
+}
+
+fn remove_component(world: &mut World, entity: Entity, type_id: TypeId) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let Some(reflect_component) = type_registry.get_type_data::<ReflectComponent>(type_id) else {
+        return;
+    };
+
+    if let Some(mut entity) = world.get_entity_mut(entity) {
+        reflect_component.remove(&mut entity);
+    }
+}
+
+struct HistoryEntry {
+    command: EditorCommand,
+    merge_key: Option<&'static str>,
+}
+
+enum HistoryAction {
+    Apply(EditorCommand, Option<&'static str>),
+    Undo,
+    Redo,
+    Seal,
+}
+
+/// Commands are queued by systems and applied all at once in [`PostUpdate`]
+#[derive(Resource, Default)]
+pub struct History {
+    undo: Vec<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+    pending: Vec<HistoryAction>,
+    /// Nothing is merged into the last entry once the interaction that produced it is over
+    sealed: bool,
+}
+
+impl History {
+    pub fn push(&mut self, command: EditorCommand) {
+        self.pending.push(HistoryAction::Apply(command, None));
+    }
+
+    /// Command is merged into the previous one with the same key, until history is sealed.
+    /// Used for continuous changes, like dragging a value, to become a single step
+    pub fn push_merged(&mut self, command: EditorCommand, merge_key: &'static str) {
+        self.pending
+            .push(HistoryAction::Apply(command, Some(merge_key)));
+    }
+
+    /// Pushes selection change, if the change actually does anything
+    pub fn change_selection(
+        &mut self,
+        active_selection: &ActiveSelection,
+        change: impl FnOnce(&mut ActiveSelection),
+    ) {
+        let mut after = active_selection.clone();
+        change(&mut after);
+
+        if after != *active_selection {
+            self.push(EditorCommand::SetSelection {
+                before: active_selection.clone(),
+                after,
+            });
+        }
+    }
+
+    pub fn undo(&mut self) {
+        self.pending.push(HistoryAction::Undo);
+    }
+
+    pub fn redo(&mut self) {
+        self.pending.push(HistoryAction::Redo);
+    }
+
+    pub fn seal(&mut self) {
+        self.pending.push(HistoryAction::Seal);
+    }
+
+    /// Undoes or redoes as many steps as needed, so given number of commands stays applied
+    pub fn jump_to(&mut self, applied: usize) {
+        let current = self.undo.len();
+
+        for _ in applied..current {
+            self.undo();
+        }
+
+        for _ in current..applied {
+            self.redo();
+        }
+    }
+
+    /// Forgets every step, e.g. when commands can't refer to the same entities anymore
+    pub fn clear(&mut self) {
+        *self = History::default();
+    }
+
+    /// Names of applied commands followed by names of undone ones
+    pub fn entries(&self) -> (Vec<String>, Vec<String>) {
+        (
+            self.undo.iter().map(|entry| entry.command.name()).collect(),
+            self.redo
+                .iter()
+                .rev()
+                .map(|entry| entry.command.name())
+                .collect(),
+        )
+    }
+
+    fn run(&mut self, action: HistoryAction, world: &mut World) {
+        match action {
+            HistoryAction::Apply(mut command, merge_key) => {
+                command.apply(world);
+                for entry in self.redo.drain(..) {
+                    entry.command.forget(world);
+                }
+
+                let command = match self.undo.last_mut() {
+                    Some(last)
+                        if !self.sealed && merge_key.is_some() && last.merge_key == merge_key =>
+                    {
+                        match last.command.merge(command) {
+                            Ok(()) => return,
+                            Err(command) => command,
+                        }
+                    }
+                    _ => command,
+                };
+
+                self.undo.push(HistoryEntry { command, merge_key });
+                self.sealed = false;
+            }
+            HistoryAction::Undo => {
+                if let Some(mut entry) = self.undo.pop() {
+                    entry.command.undo(world);
+                    self.redo.push(entry);
+                }
+                self.sealed = true;
+            }
+            HistoryAction::Redo => {
+                if let Some(mut entry) = self.redo.pop() {
+                    entry.command.apply(world);
+                    self.undo.push(entry);
+                }
+                self.sealed = true;
+            }
+            HistoryAction::Seal => {
+                self.sealed = true;
+            }
+        }
+    }
+}
+
+fn apply_history(world: &mut World) {
+    world.resource_scope(|world, mut history: bevy::ecs::world::Mut<History>| {
+        let pending = std::mem::take(&mut history.pending);
+
+        for action in pending {
+            history.run(action, world);
+        }
+    });
+}
+
+/**
+ * Releasing the mouse or confirming typed value ends the interaction,
+ * so next change becomes a separate step
+ */
+fn seal_interactions(
+    input_mouse: Res<ButtonInput<MouseButton>>,
+    input_keyboard: Res<ButtonInput<KeyCode>>,
+    mut history: ResMut<History>,
+) {
+    if input_mouse.get_just_released().next().is_some()
+        || input_keyboard.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter, KeyCode::Tab])
+    {
+        history.seal();
+    }
+}
+
+fn handle_shortcuts(mut contexts: EguiContexts, input: EditorInput, mut history: ResMut<History>) {
+    // Text fields have their own undo
+    if contexts.ctx_mut().wants_keyboard_input() {
+        return;
+    }
+
+    if input.just_pressed(InputAction::Undo) {
+        history.undo();
+    } else if input.just_pressed(InputAction::Redo) {
+        history.redo();
+    }
+}
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<History>()
+            .add_systems(Update, handle_shortcuts)
+            .add_systems(
+                PostUpdate,
+                (seal_interactions, apply_history)
+                    .chain()
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::{entity::Entity, world::World},
+        hierarchy::{BuildWorldChildren, Children},
+        render::{prelude::SpatialBundle, view::Visibility},
+        transform::components::Transform,
+    };
+
+    use crate::{active_selection::ActiveSelection, deleted::Deleted};
+
+    use super::{apply_history, EditorCommand, History, SpawnSource};
+
+    /**
+     * Parent with two children, the way they are in a scene
+     */
+    fn test_world() -> (World, Entity, [Entity; 2]) {
+        let mut world = World::new();
+        world.init_resource::<ActiveSelection>();
+        world.init_resource::<History>();
+
+        let parent = world.spawn(SpatialBundle::default()).id();
+        let children = [(); 2].map(|_| world.spawn(SpatialBundle::default()).id());
+        world.entity_mut(parent).push_children(&children);
+
+        (world, parent, children)
+    }
+
+    fn run(world: &mut World, action: impl FnOnce(&mut History)) {
+        action(&mut world.resource_mut::<History>());
+        apply_history(world);
+    }
+
+    fn delete(entities: Vec<Entity>) -> EditorCommand {
+        EditorCommand::Despawn {
+            entities,
+            deleted: Vec::new(),
+            selection: ActiveSelection::default(),
+        }
+    }
+
+    fn create_child(parent: Entity) -> EditorCommand {
+        EditorCommand::Spawn {
+            source: SpawnSource::Empty {
+                parent: Some(parent),
+            },
+            spawned: Vec::new(),
+            deleted: Vec::new(),
+            selection: ActiveSelection::default(),
+        }
+    }
+
+    fn move_to(entity: Entity, x: f32) -> EditorCommand {
+        EditorCommand::SetTransform {
+            changes: vec![(entity, Transform::default(), Transform::from_xyz(x, 0., 0.))],
+        }
+    }
+
+    fn children(world: &World, parent: Entity) -> Vec<Entity> {
+        world
+            .get::<Children>(parent)
+            .map_or_else(Vec::new, |children| children.to_vec())
+    }
+
+    fn is_deleted(world: &World, entity: Entity) -> bool {
+        world.get::<Deleted>(entity).is_some()
+    }
+
+    fn translation_x(world: &World, entity: Entity) -> f32 {
+        world.get::<Transform>(entity).unwrap().translation.x
+    }
+
+    #[test]
+    fn undo_and_redo_keep_ids_of_deleted_and_created_entities() {
+        let (mut world, parent, [first, second]) = test_world();
+
+        run(&mut world, |history| history.push(move_to(first, 1.)));
+        run(&mut world, |history| history.push(delete(vec![first])));
+        assert!(is_deleted(&world, first));
+        assert_eq!(world.get::<Visibility>(first), Some(&Visibility::Hidden));
+        assert_eq!(children(&world, parent), [second]);
+
+        run(&mut world, |history| history.push(create_child(parent)));
+        let created = world.resource::<ActiveSelection>().active().unwrap();
+        assert_ne!(created, first);
+        assert_eq!(children(&world, parent), [second, created]);
+
+        run(&mut world, History::undo);
+        assert!(is_deleted(&world, created));
+        assert_eq!(children(&world, parent), [second]);
+
+        run(&mut world, History::undo);
+        assert!(!is_deleted(&world, first));
+        assert_eq!(world.get::<Visibility>(first), Some(&Visibility::Inherited));
+        assert_eq!(children(&world, parent), [first, second]);
+
+        // Command from before the delete still reaches the entity
+        run(&mut world, History::undo);
+        assert_eq!(translation_x(&world, first), 0.);
+
+        run(&mut world, History::redo);
+        run(&mut world, History::redo);
+        assert!(is_deleted(&world, first));
+
+        run(&mut world, History::redo);
+        assert!(!is_deleted(&world, created));
+        assert_eq!(world.resource::<ActiveSelection>().active(), Some(created));
+        assert_eq!(children(&world, parent), [second, created]);
+    }
+
+    #[test]
+    fn deleted_descendants_are_hidden_with_their_ancestor() {
+        let (mut world, parent, children_before) = test_world();
+
+        run(&mut world, |history| history.push(delete(vec![parent])));
+        assert!(children_before
+            .iter()
+            .all(|child| is_deleted(&world, *child)));
+
+        run(&mut world, History::undo);
+        assert!(!is_deleted(&world, parent));
+        assert!(children_before
+            .iter()
+            .all(|child| !is_deleted(&world, *child)));
+        assert_eq!(children(&world, parent), children_before);
+    }
+
+    #[test]
+    fn undone_spawn_is_despawned_once_it_cant_be_redone() {
+        let (mut world, parent, [first, _]) = test_world();
+
+        run(&mut world, |history| history.push(create_child(parent)));
+        let created = world.resource::<ActiveSelection>().active().unwrap();
+
+        run(&mut world, History::undo);
+        assert!(world.get_entity(created).is_some());
+
+        run(&mut world, |history| history.push(move_to(first, 1.)));
+        assert!(world.get_entity(created).is_none());
+    }
+
+    #[test]
+    fn merged_changes_are_one_step_until_sealed() {
+        let (mut world, _, [first, _]) = test_world();
+
+        run(&mut world, |history| {
+            history.push_merged(move_to(first, 1.), "drag");
+            history.push_merged(move_to(first, 2.), "drag");
+        });
+        assert_eq!(world.resource::<History>().entries().0.len(), 1);
+
+        run(&mut world, |history| {
+            history.seal();
+            history.push_merged(move_to(first, 3.), "drag");
+        });
+        assert_eq!(world.resource::<History>().entries().0.len(), 2);
+        assert_eq!(translation_x(&world, first), 3.);
+
+        run(&mut world, History::undo);
+        run(&mut world, History::undo);
+        assert_eq!(translation_x(&world, first), 0.);
+
+        run(&mut world, History::redo);
+        assert_eq!(translation_x(&world, first), 2.);
+    }
+}
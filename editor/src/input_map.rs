@@ -0,0 +1,699 @@
+use std::collections::BTreeMap;
+
+use bevy::{
+    ecs::system::{Res, ResMut, Resource, SystemParam},
+    input::{keyboard::KeyCode, mouse::MouseButton, ButtonInput},
+};
+use serde::{Deserialize, Serialize};
+
+/// Something user does in the editor, that can be bound to keys and mouse buttons
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum InputAction {
+    NewScene,
+    OpenScene,
+    SaveScene,
+    SaveSceneAs,
+    Orbit,
+    Pan,
+    FlyLook,
+    FlyForward,
+    FlyBackward,
+    FlyLeft,
+    FlyRight,
+    FlyUp,
+    FlyDown,
+    FlyBoost,
+    FrameSelection,
+    ViewTop,
+    ViewBottom,
+    ViewFront,
+    ViewBack,
+    ViewLeft,
+    ViewRight,
+    ToggleProjection,
+    /// Number of the camera bookmark, 1 to 9
+    SaveBookmark(u8),
+    RecallBookmark(u8),
+    AddToSelection,
+    ToggleSelection,
+    TranslateMode,
+    RotateMode,
+    ScaleMode,
+    InvertSnap,
+    Undo,
+    Redo,
+    Rename,
+    Duplicate,
+    Delete,
+}
+
+impl InputAction {
+    /// Actions grouped the way they are listed in the bindings window
+    pub const GROUPS: [(&'static str, &'static [InputAction]); 6] = [
+        (
+            "File",
+            &[
+                InputAction::NewScene,
+                InputAction::OpenScene,
+                InputAction::SaveScene,
+                InputAction::SaveSceneAs,
+            ],
+        ),
+        (
+            "Camera",
+            &[
+                InputAction::Orbit,
+                InputAction::Pan,
+                InputAction::FlyLook,
+                InputAction::FlyForward,
+                InputAction::FlyBackward,
+                InputAction::FlyLeft,
+                InputAction::FlyRight,
+                InputAction::FlyUp,
+                InputAction::FlyDown,
+                InputAction::FlyBoost,
+                InputAction::FrameSelection,
+                InputAction::ViewTop,
+                InputAction::ViewBottom,
+                InputAction::ViewFront,
+                InputAction::ViewBack,
+                InputAction::ViewLeft,
+                InputAction::ViewRight,
+                InputAction::ToggleProjection,
+            ],
+        ),
+        (
+            "Bookmarks",
+            &[
+                InputAction::SaveBookmark(1),
+                InputAction::SaveBookmark(2),
+                InputAction::SaveBookmark(3),
+                InputAction::SaveBookmark(4),
+                InputAction::SaveBookmark(5),
+                InputAction::SaveBookmark(6),
+                InputAction::SaveBookmark(7),
+                InputAction::SaveBookmark(8),
+                InputAction::SaveBookmark(9),
+                InputAction::RecallBookmark(1),
+                InputAction::RecallBookmark(2),
+                InputAction::RecallBookmark(3),
+                InputAction::RecallBookmark(4),
+                InputAction::RecallBookmark(5),
+                InputAction::RecallBookmark(6),
+                InputAction::RecallBookmark(7),
+                InputAction::RecallBookmark(8),
+                InputAction::RecallBookmark(9),
+            ],
+        ),
+        (
+            "Selection",
+            &[InputAction::AddToSelection, InputAction::ToggleSelection],
+        ),
+        (
+            "Transform",
+            &[
+                InputAction::TranslateMode,
+                InputAction::RotateMode,
+                InputAction::ScaleMode,
+                InputAction::InvertSnap,
+            ],
+        ),
+        (
+            "Editor",
+            &[
+                InputAction::Undo,
+                InputAction::Redo,
+                InputAction::Rename,
+                InputAction::Duplicate,
+                InputAction::Delete,
+            ],
+        ),
+    ];
+
+    pub fn name(&self) -> String {
+        let name = match self {
+            InputAction::SaveBookmark(number) => return format!("Save Bookmark {number}"),
+            InputAction::RecallBookmark(number) => return format!("Recall Bookmark {number}"),
+            InputAction::NewScene => "New Scene",
+            InputAction::OpenScene => "Open Scene",
+            InputAction::SaveScene => "Save Scene",
+            InputAction::SaveSceneAs => "Save Scene As",
+            InputAction::Orbit => "Orbit",
+            InputAction::Pan => "Pan",
+            InputAction::FlyLook => "Fly Look",
+            InputAction::FlyForward => "Fly Forward",
+            InputAction::FlyBackward => "Fly Backward",
+            InputAction::FlyLeft => "Fly Left",
+            InputAction::FlyRight => "Fly Right",
+            InputAction::FlyUp => "Fly Up",
+            InputAction::FlyDown => "Fly Down",
+            InputAction::FlyBoost => "Fly Boost",
+            InputAction::FrameSelection => "Frame Selected",
+            InputAction::ViewTop => "Top View",
+            InputAction::ViewBottom => "Bottom View",
+            InputAction::ViewFront => "Front View",
+            InputAction::ViewBack => "Back View",
+            InputAction::ViewLeft => "Left View",
+            InputAction::ViewRight => "Right View",
+            InputAction::ToggleProjection => "Toggle Orthographic",
+            InputAction::AddToSelection => "Add to Selection",
+            InputAction::ToggleSelection => "Toggle Selection",
+            InputAction::TranslateMode => "Move Tool",
+            InputAction::RotateMode => "Rotate Tool",
+            InputAction::ScaleMode => "Scale Tool",
+            InputAction::InvertSnap => "Invert Snapping",
+            InputAction::Undo => "Undo",
+            InputAction::Redo => "Redo",
+            InputAction::Rename => "Rename",
+            InputAction::Duplicate => "Duplicate",
+            InputAction::Delete => "Delete",
+        };
+        name.to_owned()
+    }
+}
+
+/// Digits of the bookmarks 1 to 9
+pub const BOOKMARK_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/**
+ * Ctrl with a digit saves the bookmark and the digit alone recalls it
+ */
+fn bookmark_bindings() -> impl Iterator<Item = (InputAction, Vec<Binding>)> {
+    (1..).zip(BOOKMARK_KEYS).flat_map(|(number, key)| {
+        [
+            (
+                InputAction::SaveBookmark(number),
+                vec![Binding::key(key).with(InputModifiers::CTRL)],
+            ),
+            (InputAction::RecallBookmark(number), vec![Binding::key(key)]),
+        ]
+    })
+}
+
+/// Key or mouse button of a binding
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum InputButton {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+impl InputButton {
+    fn is_modifier(&self) -> bool {
+        matches!(self, InputButton::Key(key) if MODIFIER_KEYS.contains(key))
+    }
+}
+
+pub const MODIFIER_KEYS: [KeyCode; 6] = [
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+    KeyCode::ShiftLeft,
+    KeyCode::ShiftRight,
+    KeyCode::AltLeft,
+    KeyCode::AltRight,
+];
+
+/// Modifiers held along with the button, left and right keys are the same
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputModifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl InputModifiers {
+    pub const NONE: Self = InputModifiers {
+        ctrl: false,
+        shift: false,
+        alt: false,
+    };
+    pub const CTRL: Self = InputModifiers {
+        ctrl: true,
+        ..Self::NONE
+    };
+    pub const SHIFT: Self = InputModifiers {
+        shift: true,
+        ..Self::NONE
+    };
+    pub const ALT: Self = InputModifiers {
+        alt: true,
+        ..Self::NONE
+    };
+
+    pub fn plus(self, other: Self) -> Self {
+        InputModifiers {
+            ctrl: self.ctrl || other.ctrl,
+            shift: self.shift || other.shift,
+            alt: self.alt || other.alt,
+        }
+    }
+
+    pub fn pressed(keyboard: &ButtonInput<KeyCode>) -> Self {
+        InputModifiers {
+            ctrl: keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]),
+            shift: keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+            alt: keyboard.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct Binding {
+    pub button: InputButton,
+    #[serde(default)]
+    pub modifiers: InputModifiers,
+}
+
+impl Binding {
+    pub const fn key(key: KeyCode) -> Self {
+        Binding {
+            button: InputButton::Key(key),
+            modifiers: InputModifiers::NONE,
+        }
+    }
+
+    pub const fn mouse(button: MouseButton) -> Self {
+        Binding {
+            button: InputButton::Mouse(button),
+            modifiers: InputModifiers::NONE,
+        }
+    }
+
+    pub const fn with(self, modifiers: InputModifiers) -> Self {
+        Binding { modifiers, ..self }
+    }
+
+    /**
+     * Modifiers have to match exactly, so Ctrl+Z doesn't trigger Z.
+     * Binding of a modifier key itself ignores them, since the key is one of them
+     */
+    fn modifiers_match(&self, modifiers: InputModifiers) -> bool {
+        self.button.is_modifier() || self.modifiers == modifiers
+    }
+}
+
+impl std::fmt::Display for Binding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.modifiers.alt {
+            write!(f, "Alt+")?;
+        }
+
+        match self.button {
+            InputButton::Key(key) => {
+                let name = format!("{key:?}");
+                let name = name
+                    .strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                    .unwrap_or(&name);
+
+                match name.strip_prefix("Numpad") {
+                    Some(rest) => write!(f, "Numpad {rest}"),
+                    None => write!(f, "{name}"),
+                }
+            }
+            InputButton::Mouse(MouseButton::Other(index)) => write!(f, "Mouse {index}"),
+            InputButton::Mouse(button) => write!(f, "{button:?} Mouse"),
+        }
+    }
+}
+
+/// Control schemes close to the ones of other editors
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InputPreset {
+    Default,
+    Blender,
+    Unity,
+    /// Touchpad without middle mouse button
+    Laptop,
+}
+
+impl InputPreset {
+    pub const ALL: [InputPreset; 4] = [
+        InputPreset::Default,
+        InputPreset::Blender,
+        InputPreset::Unity,
+        InputPreset::Laptop,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InputPreset::Default => "Default",
+            InputPreset::Blender => "Blender",
+            InputPreset::Unity => "Unity",
+            InputPreset::Laptop => "Laptop (no middle mouse)",
+        }
+    }
+
+    pub fn input_map(&self) -> InputMap {
+        let mut input_map = InputMap {
+            bindings: default_bindings(),
+        };
+
+        let overrides: &[(InputAction, &[Binding])] = match self {
+            InputPreset::Default => &[],
+            InputPreset::Blender => &[
+                (InputAction::Orbit, &[Binding::mouse(MouseButton::Middle)]),
+                (
+                    InputAction::Pan,
+                    &[Binding::mouse(MouseButton::Middle).with(InputModifiers::SHIFT)],
+                ),
+                (
+                    InputAction::FrameSelection,
+                    &[Binding::key(KeyCode::NumpadDecimal)],
+                ),
+                (InputAction::TranslateMode, &[Binding::key(KeyCode::KeyG)]),
+                (InputAction::RotateMode, &[Binding::key(KeyCode::KeyR)]),
+                (InputAction::ScaleMode, &[Binding::key(KeyCode::KeyS)]),
+                (
+                    InputAction::Duplicate,
+                    &[Binding::key(KeyCode::KeyD).with(InputModifiers::SHIFT)],
+                ),
+                (
+                    InputAction::Delete,
+                    &[Binding::key(KeyCode::Delete), Binding::key(KeyCode::KeyX)],
+                ),
+            ],
+            InputPreset::Unity => &[(
+                InputAction::Orbit,
+                &[Binding::mouse(MouseButton::Left).with(InputModifiers::ALT)],
+            )],
+            InputPreset::Laptop => &[
+                (
+                    InputAction::Orbit,
+                    &[Binding::mouse(MouseButton::Left).with(InputModifiers::ALT)],
+                ),
+                (
+                    InputAction::Pan,
+                    &[Binding::mouse(MouseButton::Left)
+                        .with(InputModifiers::ALT.plus(InputModifiers::SHIFT))],
+                ),
+                // Numpad is missing as well, plain digits are taken by camera bookmark bindings
+                (
+                    InputAction::ViewTop,
+                    &[Binding::key(KeyCode::Digit7).with(InputModifiers::ALT)],
+                ),
+                (
+                    InputAction::ViewBottom,
+                    &[Binding::key(KeyCode::Digit7)
+                        .with(InputModifiers::ALT.plus(InputModifiers::CTRL))],
+                ),
+                (
+                    InputAction::ViewFront,
+                    &[Binding::key(KeyCode::Digit1).with(InputModifiers::ALT)],
+                ),
+                (
+                    InputAction::ViewBack,
+                    &[Binding::key(KeyCode::Digit1)
+                        .with(InputModifiers::ALT.plus(InputModifiers::CTRL))],
+                ),
+                (
+                    InputAction::ViewRight,
+                    &[Binding::key(KeyCode::Digit3).with(InputModifiers::ALT)],
+                ),
+                (
+                    InputAction::ViewLeft,
+                    &[Binding::key(KeyCode::Digit3)
+                        .with(InputModifiers::ALT.plus(InputModifiers::CTRL))],
+                ),
+                (
+                    InputAction::ToggleProjection,
+                    &[Binding::key(KeyCode::Digit5).with(InputModifiers::ALT)],
+                ),
+            ],
+        };
+
+        for (action, bindings) in overrides {
+            input_map.bindings.insert(*action, bindings.to_vec());
+        }
+
+        input_map
+    }
+}
+
+fn default_bindings() -> BTreeMap<InputAction, Vec<Binding>> {
+    let either = |left, right| vec![Binding::key(left), Binding::key(right)];
+    let key = |key| vec![Binding::key(key)];
+    let with = |modifiers, key| vec![Binding::key(key).with(modifiers)];
+
+    let mut bindings = BTreeMap::from([
+        (
+            InputAction::NewScene,
+            with(InputModifiers::CTRL, KeyCode::KeyN),
+        ),
+        (
+            InputAction::OpenScene,
+            with(InputModifiers::CTRL, KeyCode::KeyO),
+        ),
+        (
+            InputAction::SaveScene,
+            with(InputModifiers::CTRL, KeyCode::KeyS),
+        ),
+        (
+            InputAction::SaveSceneAs,
+            with(
+                InputModifiers::CTRL.plus(InputModifiers::SHIFT),
+                KeyCode::KeyS,
+            ),
+        ),
+        (InputAction::Orbit, vec![Binding::mouse(MouseButton::Right)]),
+        (InputAction::Pan, vec![Binding::mouse(MouseButton::Middle)]),
+        (
+            InputAction::FlyLook,
+            vec![Binding::mouse(MouseButton::Right)],
+        ),
+        (InputAction::FlyForward, key(KeyCode::KeyW)),
+        (InputAction::FlyBackward, key(KeyCode::KeyS)),
+        (InputAction::FlyLeft, key(KeyCode::KeyA)),
+        (InputAction::FlyRight, key(KeyCode::KeyD)),
+        (InputAction::FlyUp, key(KeyCode::KeyE)),
+        (InputAction::FlyDown, key(KeyCode::KeyQ)),
+        (
+            InputAction::FlyBoost,
+            either(KeyCode::ShiftLeft, KeyCode::ShiftRight),
+        ),
+        (InputAction::FrameSelection, key(KeyCode::KeyF)),
+        (InputAction::ViewTop, key(KeyCode::Numpad7)),
+        (
+            InputAction::ViewBottom,
+            with(InputModifiers::CTRL, KeyCode::Numpad7),
+        ),
+        (InputAction::ViewFront, key(KeyCode::Numpad1)),
+        (
+            InputAction::ViewBack,
+            with(InputModifiers::CTRL, KeyCode::Numpad1),
+        ),
+        (InputAction::ViewRight, key(KeyCode::Numpad3)),
+        (
+            InputAction::ViewLeft,
+            with(InputModifiers::CTRL, KeyCode::Numpad3),
+        ),
+        (InputAction::ToggleProjection, key(KeyCode::Numpad5)),
+        (
+            InputAction::AddToSelection,
+            either(KeyCode::ShiftLeft, KeyCode::ShiftRight),
+        ),
+        (
+            InputAction::ToggleSelection,
+            either(KeyCode::ControlLeft, KeyCode::ControlRight),
+        ),
+        (InputAction::TranslateMode, key(KeyCode::KeyW)),
+        (InputAction::RotateMode, key(KeyCode::KeyE)),
+        (InputAction::ScaleMode, key(KeyCode::KeyR)),
+        (
+            InputAction::InvertSnap,
+            either(KeyCode::ControlLeft, KeyCode::ControlRight),
+        ),
+        (InputAction::Undo, with(InputModifiers::CTRL, KeyCode::KeyZ)),
+        (
+            InputAction::Redo,
+            vec![
+                Binding::key(KeyCode::KeyZ).with(InputModifiers::CTRL.plus(InputModifiers::SHIFT)),
+                Binding::key(KeyCode::KeyY).with(InputModifiers::CTRL),
+            ],
+        ),
+        (InputAction::Rename, key(KeyCode::F2)),
+        (
+            InputAction::Duplicate,
+            with(InputModifiers::CTRL, KeyCode::KeyD),
+        ),
+        (InputAction::Delete, key(KeyCode::Delete)),
+    ]);
+    bindings.extend(bookmark_bindings());
+
+    bindings
+}
+
+/// Bindings of every action, an action can have several of them or none at all
+#[derive(Resource, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct InputMap {
+    bindings: BTreeMap<InputAction, Vec<Binding>>,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        InputPreset::Default.input_map()
+    }
+}
+
+impl InputMap {
+    pub fn bindings(&self, action: InputAction) -> &[Binding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn bindings_mut(&mut self, action: InputAction) -> &mut Vec<Binding> {
+        self.bindings.entry(action).or_default()
+    }
+
+    /**
+     * Actions missing from a settings file of an older version get their default bindings
+     */
+    pub fn fill_missing(&mut self) {
+        for (action, bindings) in default_bindings() {
+            self.bindings.entry(action).or_insert(bindings);
+        }
+    }
+
+    /**
+     * Same as [`EditorInput::pressed`], for systems that can't take the whole input
+     */
+    pub fn pressed(
+        &self,
+        action: InputAction,
+        keyboard: &ButtonInput<KeyCode>,
+        mouse: &ButtonInput<MouseButton>,
+    ) -> bool {
+        let modifiers = InputModifiers::pressed(keyboard);
+
+        self.bindings(action).iter().any(|binding| {
+            binding.modifiers_match(modifiers) && button_pressed(binding.button, keyboard, mouse)
+        })
+    }
+
+    /**
+     * Same as [`EditorInput::held`], for exclusive systems
+     */
+    pub fn held(
+        &self,
+        action: InputAction,
+        keyboard: &ButtonInput<KeyCode>,
+        mouse: &ButtonInput<MouseButton>,
+    ) -> bool {
+        self.bindings(action)
+            .iter()
+            .any(|binding| button_pressed(binding.button, keyboard, mouse))
+    }
+
+    /**
+     * First binding of the action, for menus and tooltips
+     */
+    pub fn shortcut_text(&self, action: InputAction) -> String {
+        self.bindings(action)
+            .first()
+            .map_or_else(String::new, Binding::to_string)
+    }
+}
+
+/// Camera drag, that started with the navigation bindings. It lasts until the frame after
+/// every mouse button is released, so neither the press nor the release is taken for a click
+#[derive(Resource, Default)]
+pub struct NavigationDrag {
+    active: bool,
+}
+
+/**
+ * Runs before the editor systems, so they already know about the drag on its first frame
+ */
+pub fn track_navigation_drag(
+    map: Res<InputMap>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut navigation: ResMut<NavigationDrag>,
+) {
+    let navigating = map.pressed(InputAction::Orbit, &keyboard, &mouse)
+        || map.pressed(InputAction::Pan, &keyboard, &mouse);
+
+    if navigating && !navigation.active {
+        navigation.active = true;
+    } else if !navigating
+        && navigation.active
+        && mouse.get_pressed().next().is_none()
+        && mouse.get_just_released().next().is_none()
+    {
+        navigation.active = false;
+    }
+}
+
+/// Input map along with the current state of keys and mouse buttons
+#[derive(SystemParam)]
+pub struct EditorInput<'w> {
+    pub map: Res<'w, InputMap>,
+    keyboard: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    navigation: Res<'w, NavigationDrag>,
+}
+
+impl EditorInput<'_> {
+    fn button_just_pressed(&self, button: InputButton) -> bool {
+        match button {
+            InputButton::Key(key) => self.keyboard.just_pressed(key),
+            InputButton::Mouse(button) => self.mouse.just_pressed(button),
+        }
+    }
+
+    /**
+     * Button is held along with exactly the modifiers of the binding
+     */
+    pub fn pressed(&self, action: InputAction) -> bool {
+        self.map.pressed(action, &self.keyboard, &self.mouse)
+    }
+
+    pub fn just_pressed(&self, action: InputAction) -> bool {
+        let modifiers = InputModifiers::pressed(&self.keyboard);
+
+        self.map.bindings(action).iter().any(|binding| {
+            binding.modifiers_match(modifiers) && self.button_just_pressed(binding.button)
+        })
+    }
+
+    /**
+     * Button is held, whatever other modifiers are. For movement keys and modifiers themselves
+     */
+    pub fn held(&self, action: InputAction) -> bool {
+        self.map.held(action, &self.keyboard, &self.mouse)
+    }
+
+    /**
+     * Camera drag is in progress or has just ended, so clicks are not meant for selection
+     * or the manipulator
+     */
+    pub fn is_navigating(&self) -> bool {
+        self.navigation.active
+    }
+}
+
+fn button_pressed(
+    button: InputButton,
+    keyboard: &ButtonInput<KeyCode>,
+    mouse: &ButtonInput<MouseButton>,
+) -> bool {
+    match button {
+        InputButton::Key(key) => keyboard.pressed(key),
+        InputButton::Mouse(button) => mouse.pressed(button),
+    }
+}
@@ -0,0 +1,38 @@
+use bevy::{
+    ecs::{
+        entity::Entity,
+        query::With,
+        system::{Query, SystemParam},
+    },
+    hierarchy::Parent,
+};
+use common::editor_components::Locked;
+
+/// Lock applies to the whole subtree, so entity is locked if any of its ancestors is
+#[derive(SystemParam)]
+pub struct LockedEntities<'w, 's> {
+    locked: Query<'w, 's, (), With<Locked>>,
+    parents: Query<'w, 's, &'static Parent>,
+}
+
+impl LockedEntities<'_, '_> {
+    pub fn is_locked(&self, entity: Entity) -> bool {
+        let mut current = Some(entity);
+
+        while let Some(entity) = current {
+            if self.locked.contains(entity) {
+                return true;
+            }
+            current = self.parents.get(entity).ok().map(Parent::get);
+        }
+
+        false
+    }
+
+    /**
+     * True if entity has the lock itself, rather than getting it from an ancestor
+     */
+    pub fn has_lock(&self, entity: Entity) -> bool {
+        self.locked.contains(entity)
+    }
+}
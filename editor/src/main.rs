@@ -1,13 +1,37 @@
 mod active_selection;
+mod args;
+mod box_selection;
 mod camera;
+mod deleted;
+mod history;
+mod input_map;
+mod lock;
+mod manipulator;
+mod sample_scene;
+mod scene_file;
+mod settings;
+mod snapshot;
+mod stress_scene;
 mod ui;
 
-use bevy::prelude::*;
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    prelude::*,
+};
 use bevy_egui::EguiPlugin;
+use bevy_mod_picking::pointer::PointerId;
 use bevy_mod_picking::DefaultPickingPlugins;
+use common::{
+    editor_components::{EditorComponentsPlugin, EditorOnly},
+    scene_assets::SceneAssetsPlugin,
+};
 use ui::EditorUiPlugin;
 
-use crate::{active_selection::ActiveSelectionPlugin, camera::EditorCameraPlugin};
+use crate::{
+    active_selection::ActiveSelectionPlugin, args::EditorArgs, box_selection::BoxSelectionPlugin,
+    camera::EditorCameraPlugin, history::HistoryPlugin, manipulator::ManipulatorPlugin,
+    scene_file::SceneFilePlugin, settings::EditorSettingsPlugin,
+};
 
 fn init(mut config_store: ResMut<GizmoConfigStore>) {
     for (_, config, _) in config_store.iter_mut() {
@@ -15,27 +39,57 @@ fn init(mut config_store: ResMut<GizmoConfigStore>) {
     }
 }
 
+/**
+ * Windows and picking pointers are spawned by plugins, so they are marked once they appear
+ */
+fn mark_editor_entities(
+    mut commands: Commands,
+    entities: Query<Entity, (Or<(With<Window>, With<PointerId>)>, Without<EditorOnly>)>,
+) {
+    for entity in &entities {
+        commands.entity(entity).insert(EditorOnly);
+    }
+}
+
 fn main() {
     println!("Editor starting...");
 
-    App::new()
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Editor".to_owned(),
-                resize_constraints: WindowResizeConstraints {
-                    min_width: 1280.,
-                    min_height: 720.,
-                    ..Default::default()
-                },
+    let args = EditorArgs::from_env();
+    let log_diagnostics = args.stress.is_some();
+
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins.set(WindowPlugin {
+        primary_window: Some(Window {
+            title: "Editor".to_owned(),
+            resize_constraints: WindowResizeConstraints {
+                min_width: 1280.,
+                min_height: 720.,
                 ..Default::default()
-            }),
+            },
             ..Default::default()
-        }))
-        .add_systems(Startup, init)
-        .add_plugins(EditorCameraPlugin)
-        .add_plugins(EguiPlugin)
-        .add_plugins(DefaultPickingPlugins)
-        .add_plugins(ActiveSelectionPlugin)
-        .add_plugins(EditorUiPlugin)
-        .run();
+        }),
+        ..Default::default()
+    }))
+    .insert_resource(args)
+    .add_systems(Startup, init)
+    .add_systems(PreUpdate, mark_editor_entities)
+    .add_plugins(EditorSettingsPlugin)
+    .add_plugins(HistoryPlugin)
+    .add_plugins(SceneAssetsPlugin)
+    .add_plugins(EditorComponentsPlugin)
+    .add_plugins(SceneFilePlugin)
+    .add_plugins(EditorCameraPlugin)
+    .add_plugins(EguiPlugin)
+    .add_plugins(DefaultPickingPlugins)
+    .add_plugins(ActiveSelectionPlugin)
+    .add_plugins(BoxSelectionPlugin)
+    .add_plugins(ManipulatorPlugin)
+    .add_plugins(EditorUiPlugin);
+
+    // Frame time next to the time spent on editor panels
+    if log_diagnostics {
+        app.add_plugins((FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin::default()));
+    }
+
+    app.run();
 }
@@ -0,0 +1,638 @@
+use bevy::{
+    app::{Plugin, Update},
+    ecs::{
+        entity::Entity,
+        query::With,
+        schedule::IntoSystemConfigs,
+        system::{Query, Res, ResMut, Resource, SystemParam},
+    },
+    gizmos::gizmos::Gizmos,
+    hierarchy::Parent,
+    input::{mouse::MouseButton, ButtonInput},
+    math::{
+        primitives::{Direction3d, Plane3d},
+        Quat, Ray3d, Vec2, Vec3,
+    },
+    render::{camera::Camera, color::Color},
+    transform::components::{GlobalTransform, Transform},
+    window::{PrimaryWindow, Window},
+};
+use bevy_egui::EguiContexts;
+
+use crate::{
+    active_selection::ActiveSelection,
+    camera::{viewport_cursor, PanOrbitCamera},
+    history::{EditorCommand, History},
+    input_map::{EditorInput, InputAction},
+    lock::LockedEntities,
+    settings::{EditorSettings, SnapSettings, TransformSpace},
+};
+
+/// Distance in logical pixels from the handle at which it is considered hovered
+const HOVER_THRESHOLD: f32 = 8.;
+
+/// Size of the manipulator relative to its distance from the camera, so it has the same size on screen
+const SCREEN_SIZE: f32 = 0.2;
+
+/// Plane handles are drawn as squares between these fractions of the axis length
+const PLANE_HANDLE_RANGE: (f32, f32) = (0.2, 0.45);
+
+const AXIS_COLORS: [Color; 3] = [Color::RED, Color::GREEN, Color::BLUE];
+
+const HOVER_COLOR: Color = Color::YELLOW;
+
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ManipulatorMode {
+    #[default]
+    Translate,
+    Rotate,
+    Scale,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ManipulatorHandle {
+    /// Moving along, rotating around or scaling along one of the axes
+    Axis(usize),
+    /// Moving in the plane with the normal of one of the axes
+    Plane(usize),
+    /// Uniform scaling
+    Center,
+}
+
+struct Drag {
+    handle: ManipulatorHandle,
+    /// Manipulator stays where it was when drag started
+    frame: ManipulatorFrame,
+    start_point: Vec3,
+    start_cursor: Vec2,
+    /// World transform of the active entity, its values are the ones snapped
+    start_target: Transform,
+    /// World transform of every dragged entity and global transform of its parent at drag start
+    start_transforms: Vec<(Entity, Transform, Option<GlobalTransform>)>,
+}
+
+#[derive(Resource, Default)]
+pub struct Manipulator {
+    hovered: Option<ManipulatorHandle>,
+    drag: Option<Drag>,
+}
+
+impl Manipulator {
+    /// Clicks should not change the selection while handle is hovered or dragged
+    pub fn is_active(&self) -> bool {
+        self.hovered.is_some() || self.drag.is_some()
+    }
+
+    /// Camera stays still while handle is dragged, hovering alone doesn't stop navigation
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    fn is_highlighted(&self, handle: ManipulatorHandle) -> bool {
+        match &self.drag {
+            Some(drag) => drag.handle == handle,
+            None => self.hovered == Some(handle),
+        }
+    }
+}
+
+/// Position, orientation and size of the manipulator in the world
+struct ManipulatorFrame {
+    origin: Vec3,
+    axes: [Vec3; 3],
+    size: f32,
+}
+
+impl ManipulatorFrame {
+    fn new(
+        target: &GlobalTransform,
+        camera_transform: &GlobalTransform,
+        mode: ManipulatorMode,
+        space: TransformSpace,
+    ) -> Self {
+        let (_, rotation, origin) = target.to_scale_rotation_translation();
+
+        // Scale is stored along entity's own axes, so scaling along world axes can't be applied
+        let space = match mode {
+            ManipulatorMode::Scale => TransformSpace::Local,
+            _ => space,
+        };
+
+        let axes = match space {
+            TransformSpace::Local => [rotation * Vec3::X, rotation * Vec3::Y, rotation * Vec3::Z],
+            TransformSpace::World => [Vec3::X, Vec3::Y, Vec3::Z],
+        };
+
+        ManipulatorFrame {
+            origin,
+            axes,
+            size: camera_transform.translation().distance(origin) * SCREEN_SIZE,
+        }
+    }
+
+    /// Two axes spanning the plane with the normal of given axis
+    fn plane_axes(&self, normal: usize) -> (Vec3, Vec3) {
+        (self.axes[(normal + 1) % 3], self.axes[(normal + 2) % 3])
+    }
+
+    fn plane_corners(&self, normal: usize) -> [Vec3; 4] {
+        let (u, v) = self.plane_axes(normal);
+        let (from, to) = PLANE_HANDLE_RANGE;
+
+        [(from, from), (to, from), (to, to), (from, to)]
+            .map(|(a, b)| self.origin + (u * a + v * b) * self.size)
+    }
+
+    fn ring_points(&self, axis: usize) -> impl Iterator<Item = Vec3> + '_ {
+        let (u, v) = self.plane_axes(axis);
+
+        (0..=32).map(move |step| {
+            let angle = step as f32 / 32. * std::f32::consts::TAU;
+            self.origin + (u * angle.cos() + v * angle.sin()) * self.size
+        })
+    }
+
+    fn handles(mode: ManipulatorMode) -> Vec<ManipulatorHandle> {
+        let axes = (0..3).map(ManipulatorHandle::Axis);
+
+        match mode {
+            ManipulatorMode::Translate => {
+                axes.chain((0..3).map(ManipulatorHandle::Plane)).collect()
+            }
+            ManipulatorMode::Rotate => axes.collect(),
+            ManipulatorMode::Scale => axes.chain([ManipulatorHandle::Center]).collect(),
+        }
+    }
+
+    /// Point on the constraint of the handle, which is under the cursor
+    fn constrained_point(
+        &self,
+        mode: ManipulatorMode,
+        handle: ManipulatorHandle,
+        ray: Ray3d,
+    ) -> Option<Vec3> {
+        match (mode, handle) {
+            (ManipulatorMode::Rotate, ManipulatorHandle::Axis(axis))
+            | (_, ManipulatorHandle::Plane(axis)) => {
+                let normal = Direction3d::new(self.axes[axis]).ok()?;
+                let distance = ray.intersect_plane(self.origin, Plane3d { normal })?;
+                Some(ray.get_point(distance))
+            }
+            (_, ManipulatorHandle::Axis(axis)) => {
+                closest_point_on_line(ray, self.origin, self.axes[axis])
+            }
+            (_, ManipulatorHandle::Center) => Some(self.origin),
+        }
+    }
+
+    /// Distance in logical pixels from the cursor to the handle
+    fn hover_distance(
+        &self,
+        mode: ManipulatorMode,
+        handle: ManipulatorHandle,
+        cursor: Vec2,
+        ray: Ray3d,
+        project: impl Fn(Vec3) -> Option<Vec2>,
+    ) -> Option<f32> {
+        match (mode, handle) {
+            (ManipulatorMode::Rotate, ManipulatorHandle::Axis(axis)) => {
+                let points = self
+                    .ring_points(axis)
+                    .map(&project)
+                    .collect::<Option<Vec<_>>>()?;
+
+                points
+                    .windows(2)
+                    .map(|segment| distance_to_segment(cursor, segment[0], segment[1]))
+                    .reduce(f32::min)
+            }
+            (_, ManipulatorHandle::Axis(axis)) => {
+                let from = project(self.origin)?;
+                let to = project(self.origin + self.axes[axis] * self.size)?;
+                Some(distance_to_segment(cursor, from, to))
+            }
+            (_, ManipulatorHandle::Plane(axis)) => {
+                let point = self.constrained_point(mode, handle, ray)?;
+                let (u, v) = self.plane_axes(axis);
+                let offset = (point - self.origin) / self.size;
+                let (from, to) = PLANE_HANDLE_RANGE;
+                let inside =
+                    (from..=to).contains(&offset.dot(u)) && (from..=to).contains(&offset.dot(v));
+
+                inside.then_some(0.)
+            }
+            (_, ManipulatorHandle::Center) => Some(project(self.origin)?.distance(cursor)),
+        }
+    }
+}
+
+/// Closest point on the line to the ray
+fn closest_point_on_line(ray: Ray3d, origin: Vec3, direction: Vec3) -> Option<Vec3> {
+    let ray_direction = *ray.direction;
+    let offset = ray.origin - origin;
+
+    let b = direction.dot(ray_direction);
+    let denominator = 1. - b * b;
+
+    // Ray is parallel to the line
+    if denominator.abs() < 1e-5 {
+        return None;
+    }
+
+    let t = (direction.dot(offset) - b * ray_direction.dot(offset)) / denominator;
+    Some(origin + direction * t)
+}
+
+fn distance_to_segment(point: Vec2, from: Vec2, to: Vec2) -> f32 {
+    let segment = to - from;
+    let t =
+        ((point - from).dot(segment) / segment.length_squared().max(f32::EPSILON)).clamp(0., 1.);
+
+    point.distance(from + segment * t)
+}
+
+/// Camera and transforms of the manipulated entities
+#[derive(SystemParam)]
+pub struct ManipulatorQueries<'w, 's> {
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<PanOrbitCamera>>,
+    global_transforms: Query<'w, 's, &'static GlobalTransform>,
+    parents: Query<'w, 's, &'static Parent>,
+    transforms: Query<'w, 's, &'static Transform>,
+}
+
+/// Skips entities whose ancestor is selected too, as they are already moved with it
+fn has_selected_ancestor(
+    entity: Entity,
+    active_selection: &ActiveSelection,
+    parents: &Query<&Parent>,
+) -> bool {
+    let mut current = entity;
+    while let Ok(parent) = parents.get(current) {
+        current = parent.get();
+        if active_selection.is_selected(current) {
+            return true;
+        }
+    }
+
+    false
+}
+
+fn switch_mode(mut contexts: EguiContexts, input: EditorInput, mut mode: ResMut<ManipulatorMode>) {
+    // Movement keys may be the same while flying
+    if contexts.ctx_mut().wants_keyboard_input() || input.held(InputAction::FlyLook) {
+        return;
+    }
+
+    if input.just_pressed(InputAction::TranslateMode) {
+        *mode = ManipulatorMode::Translate;
+    } else if input.just_pressed(InputAction::RotateMode) {
+        *mode = ManipulatorMode::Rotate;
+    } else if input.just_pressed(InputAction::ScaleMode) {
+        *mode = ManipulatorMode::Scale;
+    }
+}
+
+pub fn update_manipulator(
+    mut contexts: EguiContexts,
+    input: EditorInput,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    input_mouse: Res<ButtonInput<MouseButton>>,
+    mode: Res<ManipulatorMode>,
+    settings: Res<EditorSettings>,
+    mut manipulator: ResMut<Manipulator>,
+    active_selection: Res<ActiveSelection>,
+    queries: ManipulatorQueries,
+    locked: LockedEntities,
+    mut history: ResMut<History>,
+) {
+    let ManipulatorQueries {
+        cameras,
+        global_transforms,
+        parents,
+        transforms,
+    } = queries;
+
+    // Keeping the drag through the frame it was released, so the click is not treated as selection
+    if !input_mouse.pressed(MouseButton::Left) && !input_mouse.just_released(MouseButton::Left) {
+        manipulator.drag = None;
+    }
+
+    let target = active_selection
+        .active()
+        .filter(|entity| !locked.is_locked(*entity))
+        .and_then(|entity| global_transforms.get(entity).ok());
+
+    let (Some(target), Ok((camera, camera_transform))) = (target, cameras.get_single()) else {
+        manipulator.hovered = None;
+        manipulator.drag = None;
+        return;
+    };
+
+    let Some(cursor) = windows
+        .get_single()
+        .ok()
+        .and_then(|window| viewport_cursor(camera, window))
+    else {
+        return;
+    };
+
+    let Some(ray) = camera.viewport_to_world(camera_transform, cursor) else {
+        return;
+    };
+
+    if let Some(drag) = &manipulator.drag {
+        if input_mouse.pressed(MouseButton::Left) {
+            let invert_snap = input.held(InputAction::InvertSnap);
+            let snap = Some(&settings.snap).filter(|snap| snap.is_active(invert_snap));
+
+            apply_drag(
+                drag,
+                *mode,
+                snap,
+                camera,
+                camera_transform,
+                cursor,
+                ray,
+                &transforms,
+                &mut history,
+            );
+        }
+        return;
+    }
+
+    let frame = ManipulatorFrame::new(target, camera_transform, *mode, settings.transform_space);
+
+    manipulator.hovered = if contexts.ctx_mut().is_pointer_over_area() {
+        None
+    } else {
+        ManipulatorFrame::handles(*mode)
+            .into_iter()
+            .filter_map(|handle| {
+                let distance = frame.hover_distance(*mode, handle, cursor, ray, |point| {
+                    camera.world_to_viewport(camera_transform, point)
+                })?;
+                (distance < HOVER_THRESHOLD).then_some((handle, distance))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(handle, _)| handle)
+    };
+
+    let Some(handle) = manipulator.hovered else {
+        return;
+    };
+
+    // Click of a camera drag, like Alt+LMB orbit, is not meant for the handle
+    if !input_mouse.just_pressed(MouseButton::Left) || input.is_navigating() {
+        return;
+    }
+
+    let Some(start_point) = frame.constrained_point(*mode, handle, ray) else {
+        return;
+    };
+
+    let start_transforms = active_selection
+        .entities()
+        .iter()
+        .filter(|entity| !has_selected_ancestor(**entity, &active_selection, &parents))
+        .filter(|entity| !locked.is_locked(**entity))
+        .filter_map(|entity| {
+            let transform = global_transforms.get(*entity).ok()?.compute_transform();
+            let parent = parents
+                .get(*entity)
+                .ok()
+                .and_then(|parent| global_transforms.get(parent.get()).ok())
+                .copied();
+
+            Some((*entity, transform, parent))
+        })
+        .collect();
+
+    manipulator.drag = Some(Drag {
+        handle,
+        frame,
+        start_point,
+        start_cursor: cursor,
+        start_target: target.compute_transform(),
+        start_transforms,
+    });
+}
+
+fn apply_drag(
+    drag: &Drag,
+    mode: ManipulatorMode,
+    snap: Option<&SnapSettings>,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    cursor: Vec2,
+    ray: Ray3d,
+    transforms: &Query<&Transform>,
+    history: &mut History,
+) {
+    let frame = &drag.frame;
+
+    let Some(point) = frame.constrained_point(mode, drag.handle, ray) else {
+        return;
+    };
+
+    let from = drag.start_point - frame.origin;
+    let to = point - frame.origin;
+
+    let start_target = &drag.start_target;
+
+    // Active entity lands on the grid along the axes it's moved on,
+    // the rest move by the same amount, so offsets between entities are kept
+    let mut delta = point - drag.start_point;
+    if let Some(snap) = snap {
+        let moved_axes = match drag.handle {
+            ManipulatorHandle::Axis(axis) => vec![axis],
+            ManipulatorHandle::Plane(normal) => (0..3).filter(|axis| *axis != normal).collect(),
+            ManipulatorHandle::Center => vec![0, 1, 2],
+        };
+
+        delta = (0..3)
+            .map(|index| {
+                let axis = frame.axes[index];
+                let moved = delta.dot(axis);
+                if !moved_axes.contains(&index) {
+                    return axis * moved;
+                }
+
+                let start = start_target.translation.dot(axis);
+                axis * (snap.translation(start + moved) - start)
+            })
+            .sum();
+    }
+
+    let mut changes = Vec::new();
+
+    for (entity, start, parent) in &drag.start_transforms {
+        let mut world = *start;
+        let offset = start.translation - frame.origin;
+
+        match (mode, drag.handle) {
+            (ManipulatorMode::Translate, _) => {
+                world.translation += delta;
+            }
+            (ManipulatorMode::Rotate, ManipulatorHandle::Axis(axis)) => {
+                let axis = frame.axes[axis];
+                let mut angle = from.cross(to).dot(axis).atan2(from.dot(to));
+                if let Some(snap) = snap {
+                    let start_angle = twist_angle(start_target.rotation, axis);
+                    angle = snap.rotation(start_angle + angle) - start_angle;
+                }
+                let rotation = Quat::from_axis_angle(axis, angle);
+
+                world.rotation = rotation * start.rotation;
+                world.translation = frame.origin + rotation * offset;
+            }
+            (ManipulatorMode::Scale, ManipulatorHandle::Axis(axis)) => {
+                let direction = frame.axes[axis];
+                let start_length = from.dot(direction);
+                if start_length.abs() < f32::EPSILON {
+                    continue;
+                }
+                let factor = snap_factor(
+                    to.dot(direction) / start_length,
+                    start_target.scale[axis],
+                    snap,
+                );
+
+                // Frame is in local space of the active entity, entities rotated differently
+                // scale along their own axis, that is the closest to the dragged one
+                let local_direction = (start.rotation.inverse() * direction).abs();
+                let local_axis = (0..3)
+                    .max_by(|a, b| local_direction[*a].total_cmp(&local_direction[*b]))
+                    .unwrap_or(axis);
+                world.scale[local_axis] *= factor;
+                world.translation += direction * offset.dot(direction) * (factor - 1.);
+            }
+            (ManipulatorMode::Scale, ManipulatorHandle::Center) => {
+                let Some(center) = camera.world_to_viewport(camera_transform, frame.origin) else {
+                    continue;
+                };
+                let start_distance = drag.start_cursor.distance(center);
+                if start_distance < f32::EPSILON {
+                    continue;
+                }
+                // Uniform scale snaps the largest scale of the active entity
+                let factor = snap_factor(
+                    cursor.distance(center) / start_distance,
+                    start_target.scale.max_element(),
+                    snap,
+                );
+
+                world.scale *= factor;
+                world.translation = frame.origin + offset * factor;
+            }
+            _ => continue,
+        }
+
+        // Manipulator works in world space, but entity stores transform relative to its parent
+        let local = match parent {
+            Some(parent) => GlobalTransform::from(world).reparented_to(parent),
+            None => world,
+        };
+
+        if let Ok(transform) = transforms.get(*entity) {
+            if *transform != local {
+                changes.push((*entity, *transform, local));
+            }
+        }
+    }
+
+    // Whole drag becomes a single undo step
+    if !changes.is_empty() {
+        history.push_merged(EditorCommand::SetTransform { changes }, "manipulator");
+    }
+}
+
+/// Factor, that puts the start scale of the active entity onto the grid
+fn snap_factor(factor: f32, start_scale: f32, snap: Option<&SnapSettings>) -> f32 {
+    match snap {
+        Some(snap) if start_scale.abs() > f32::EPSILON => {
+            snap.scale(start_scale * factor) / start_scale
+        }
+        _ => factor,
+    }
+}
+
+/// Angle of the rotation around the axis, leaving out rotation around the other axes
+fn twist_angle(rotation: Quat, axis: Vec3) -> f32 {
+    2. * rotation.xyz().dot(axis).atan2(rotation.w)
+}
+
+fn draw_manipulator(
+    mode: Res<ManipulatorMode>,
+    settings: Res<EditorSettings>,
+    manipulator: Res<Manipulator>,
+    active_selection: Res<ActiveSelection>,
+    cameras: Query<&GlobalTransform, With<PanOrbitCamera>>,
+    global_transforms: Query<&GlobalTransform>,
+    locked: LockedEntities,
+    mut gizmos: Gizmos,
+) {
+    // Locked entity has no manipulator
+    let target = active_selection
+        .active()
+        .filter(|entity| !locked.is_locked(*entity))
+        .and_then(|entity| global_transforms.get(entity).ok());
+
+    let (Some(target), Ok(camera_transform)) = (target, cameras.get_single()) else {
+        return;
+    };
+
+    let frame = ManipulatorFrame::new(target, camera_transform, *mode, settings.transform_space);
+
+    for handle in ManipulatorFrame::handles(*mode) {
+        let color = match handle {
+            _ if manipulator.is_highlighted(handle) => HOVER_COLOR,
+            ManipulatorHandle::Axis(axis) | ManipulatorHandle::Plane(axis) => AXIS_COLORS[axis],
+            ManipulatorHandle::Center => Color::WHITE,
+        };
+
+        match (*mode, handle) {
+            (ManipulatorMode::Translate, ManipulatorHandle::Axis(axis)) => {
+                gizmos.arrow(
+                    frame.origin,
+                    frame.origin + frame.axes[axis] * frame.size,
+                    color,
+                );
+            }
+            (ManipulatorMode::Rotate, ManipulatorHandle::Axis(axis)) => {
+                gizmos.linestrip(frame.ring_points(axis), color);
+            }
+            (ManipulatorMode::Scale, ManipulatorHandle::Axis(axis)) => {
+                let end = frame.origin + frame.axes[axis] * frame.size;
+                gizmos.line(frame.origin, end, color);
+                gizmos.cuboid(
+                    Transform::from_translation(end).with_scale(Vec3::splat(frame.size * 0.08)),
+                    color,
+                );
+            }
+            (_, ManipulatorHandle::Plane(axis)) => {
+                let corners = frame.plane_corners(axis);
+                gizmos.linestrip(corners.into_iter().chain([corners[0]]), color);
+            }
+            (_, ManipulatorHandle::Center) => {
+                gizmos.cuboid(
+                    Transform::from_translation(frame.origin)
+                        .with_scale(Vec3::splat(frame.size * 0.12)),
+                    color,
+                );
+            }
+        }
+    }
+}
+
+pub struct ManipulatorPlugin;
+
+impl Plugin for ManipulatorPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<ManipulatorMode>()
+            .init_resource::<Manipulator>()
+            .add_systems(
+                Update,
+                (switch_mode, update_manipulator, draw_manipulator).chain(),
+            );
+    }
+}
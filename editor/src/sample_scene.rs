@@ -0,0 +1,72 @@
+use bevy::{
+    core::Name,
+    ecs::world::World,
+    hierarchy::BuildWorldChildren,
+    math::Vec2,
+    pbr::{AmbientLight, PbrBundle, PointLight, PointLightBundle},
+    render::color::Color,
+    transform::components::Transform,
+};
+use bevy_mod_picking::PickableBundle;
+use common::scene_assets::{MaterialSource, MeshSource};
+
+/**
+ * Spawns the demo scene: a plane with a few nested cuboids and a point light
+ */
+pub fn spawn(world: &mut World) {
+    world.insert_resource(AmbientLight {
+        color: Color::WHITE,
+        brightness: 50.,
+    });
+
+    world.spawn((
+        PbrBundle::default(),
+        MeshSource::Plane {
+            half_size: Vec2::splat(2.5),
+        },
+        MaterialSource::from(Color::rgb(0.3, 0.5, 0.3)),
+        Name::new("Plane"),
+        PickableBundle::default(),
+    ));
+
+    world.spawn(cuboid(Transform::from_xyz(0.0, 0.5, 0.0)));
+
+    world.spawn(cuboid(Transform::from_xyz(0.0, -1.0, 0.0)));
+
+    world
+        .spawn(cuboid(Transform::from_xyz(1.0, 3.0, 1.0)))
+        .with_children(|parent| {
+            parent
+                .spawn(cuboid(Transform::from_xyz(2.0, 4.0, 2.0)))
+                .with_children(|parent| {
+                    parent.spawn(cuboid(Transform::from_xyz(2.0, -2.0, 2.0)));
+                });
+        });
+
+    world.spawn((
+        PointLightBundle {
+            point_light: PointLight {
+                intensity: 20500.0,
+                shadows_enabled: true,
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(1.0, 1.0, 1.0),
+            ..Default::default()
+        },
+        Name::new("Point Light"),
+        PickableBundle::default(),
+    ));
+}
+
+fn cuboid(transform: Transform) -> (PbrBundle, MeshSource, MaterialSource, Name, PickableBundle) {
+    (
+        PbrBundle {
+            transform,
+            ..Default::default()
+        },
+        MeshSource::default(),
+        MaterialSource::from(Color::rgb(0.8, 0.7, 0.6)),
+        Name::new("Cuboid"),
+        PickableBundle::default(),
+    )
+}
@@ -0,0 +1,297 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    app::{Plugin, PostUpdate, Startup},
+    asset::Handle,
+    ecs::{
+        entity::{Entity, EntityHashMap},
+        event::{Event, EventWriter, Events},
+        query::{With, Without},
+        reflect::AppTypeRegistry,
+        schedule::IntoSystemConfigs,
+        system::{Res, Resource},
+        world::World,
+    },
+    log::{error, info, warn},
+    pbr::{AmbientLight, StandardMaterial},
+    render::{mesh::Mesh, primitives::Aabb},
+    scene::{serde::SceneDeserializer, DynamicSceneBuilder},
+    transform::{components::Transform, TransformSystem},
+};
+use bevy_mod_picking::{focus::PickingInteraction, Pickable};
+use common::editor_components::EditorOnly;
+use serde::{de::DeserializeSeed, Deserialize, Serialize};
+
+use crate::{
+    active_selection::ActiveSelection, args::EditorArgs, camera::CameraView, deleted::Deleted,
+    history::History, sample_scene, snapshot::make_pickable, stress_scene,
+};
+
+pub const SCENE_EXTENSION: &str = "scn.ron";
+
+/// Scene file currently opened in the editor
+#[derive(Resource, Default)]
+pub struct SceneFile {
+    pub path: Option<PathBuf>,
+}
+
+/// Editor state of the opened scene, that isn't part of the scene itself.
+/// Stored in a file next to the scene, so it's there when the scene is opened again
+#[derive(Resource, Default, Serialize, Deserialize, Debug)]
+pub struct SceneEditorState {
+    /// Name paths of expanded hierarchy nodes, entity ids change between sessions
+    #[serde(default)]
+    pub expanded: Vec<String>,
+    /// Camera views saved under numbers 1 to 9
+    #[serde(default)]
+    pub bookmarks: BTreeMap<u8, CameraView>,
+}
+
+#[derive(Event)]
+pub struct SaveScene {
+    pub path: PathBuf,
+}
+
+/// Replaces everything in the edited world
+#[derive(Event)]
+pub enum LoadScene {
+    Empty,
+    Sample,
+    /// Generated scene with the given number of entities
+    Stress(usize),
+    File(PathBuf),
+}
+
+/// Sent once the scene is replaced and its `SceneEditorState` is loaded
+#[derive(Event)]
+pub struct SceneLoaded;
+
+/**
+ * Appends scene extension, if path doesn't have it already
+ */
+pub fn with_scene_extension(path: impl Into<PathBuf>) -> PathBuf {
+    let path = path.into();
+
+    if path.to_string_lossy().ends_with(SCENE_EXTENSION) {
+        return path;
+    }
+
+    let mut path = path.into_os_string();
+    path.push(".");
+    path.push(SCENE_EXTENSION);
+    path.into()
+}
+
+/**
+ * Path of the editor state file, `level.scn.ron` keeps it in `level.scn.editor.ron`
+ */
+pub fn editor_state_path(scene_path: &Path) -> PathBuf {
+    scene_path.with_extension("editor.ron")
+}
+
+/**
+ * Entities that are part of the edited world
+ */
+fn scene_entities(world: &mut World) -> Vec<Entity> {
+    world
+        .query_filtered::<Entity, (With<Transform>, Without<EditorOnly>, Without<Deleted>)>()
+        .iter(world)
+        .collect()
+}
+
+fn write_scene(world: &mut World, path: &Path) -> Result<(), Box<dyn Error>> {
+    let entities = scene_entities(world);
+
+    // Asset handles are stored through `MeshSource` and `MaterialSource`,
+    // bounds are recomputed from the mesh. Picking is added back by the editor on load,
+    // so games don't need its types
+    let scene = DynamicSceneBuilder::from_world(world)
+        .deny::<Handle<Mesh>>()
+        .deny::<Handle<StandardMaterial>>()
+        .deny::<Aabb>()
+        .deny::<Pickable>()
+        .deny::<PickingInteraction>()
+        .allow_resource::<AmbientLight>()
+        .extract_entities(entities.into_iter())
+        .extract_resources()
+        .build();
+
+    let serialized = scene.serialize_ron(world.resource::<AppTypeRegistry>())?;
+
+    std::fs::write(path, serialized)?;
+
+    Ok(())
+}
+
+fn read_scene(world: &mut World, path: &Path) -> Result<(), Box<dyn Error>> {
+    let bytes = std::fs::read(path)?;
+
+    // Deserializing before touching the world, so broken file leaves current scene as it is
+    let scene = {
+        let type_registry = world.resource::<AppTypeRegistry>().read();
+        let mut deserializer = ron::de::Deserializer::from_bytes(&bytes)?;
+        SceneDeserializer {
+            type_registry: &type_registry,
+        }
+        .deserialize(&mut deserializer)?
+    };
+
+    clear_scene(world);
+
+    let mut entity_map = EntityHashMap::default();
+    scene.write_to_world(world, &mut entity_map)?;
+
+    make_pickable(world, entity_map.into_values());
+
+    Ok(())
+}
+
+fn write_editor_state(world: &World, scene_path: &Path) -> Result<(), Box<dyn Error>> {
+    let serialized = ron::ser::to_string_pretty(
+        world.resource::<SceneEditorState>(),
+        ron::ser::PrettyConfig::default(),
+    )?;
+
+    std::fs::write(editor_state_path(scene_path), serialized)?;
+
+    Ok(())
+}
+
+/**
+ * Scene without the editor state file, or with a broken one, still opens
+ */
+fn read_editor_state(scene_path: &Path) -> SceneEditorState {
+    let path = editor_state_path(scene_path);
+
+    let Ok(serialized) = std::fs::read_to_string(&path) else {
+        return SceneEditorState::default();
+    };
+
+    ron::from_str(&serialized).unwrap_or_else(|err| {
+        warn!("Failed to read editor state from {}: {err}", path.display());
+        SceneEditorState::default()
+    })
+}
+
+fn clear_scene(world: &mut World) {
+    // Children have transforms too, so everything is despawned without recursion.
+    // Deleted entities go as well, as history that could restore them is cleared
+    let deleted = world
+        .query_filtered::<Entity, With<Deleted>>()
+        .iter(world)
+        .collect::<Vec<_>>();
+    for entity in scene_entities(world).into_iter().chain(deleted) {
+        world.despawn(entity);
+    }
+
+    world.insert_resource(AmbientLight::default());
+}
+
+/**
+ * Selection and history refer to entities of the previous scene
+ */
+fn reset_editor_state(world: &mut World) {
+    world.resource_mut::<ActiveSelection>().clear();
+    world.resource_mut::<History>().clear();
+}
+
+pub fn save_scene(world: &mut World) {
+    let requests = world
+        .resource_mut::<Events<SaveScene>>()
+        .drain()
+        .collect::<Vec<_>>();
+
+    for SaveScene { path } in requests {
+        match write_scene(world, &path) {
+            Ok(()) => {
+                info!("Scene saved to {}", path.display());
+
+                if let Err(err) = write_editor_state(world, &path) {
+                    warn!("Failed to save editor state of {}: {err}", path.display());
+                }
+
+                world.resource_mut::<SceneFile>().path = Some(path);
+            }
+            Err(err) => error!("Failed to save scene to {}: {err}", path.display()),
+        }
+    }
+}
+
+pub fn load_scene(world: &mut World) {
+    let requests = world
+        .resource_mut::<Events<LoadScene>>()
+        .drain()
+        .collect::<Vec<_>>();
+
+    for request in requests {
+        let editor_state = match request {
+            LoadScene::Empty => {
+                clear_scene(world);
+                world.resource_mut::<SceneFile>().path = None;
+                SceneEditorState::default()
+            }
+            LoadScene::Sample => {
+                clear_scene(world);
+                sample_scene::spawn(world);
+                world.resource_mut::<SceneFile>().path = None;
+                SceneEditorState::default()
+            }
+            LoadScene::Stress(count) => {
+                clear_scene(world);
+                stress_scene::spawn(world, count);
+                world.resource_mut::<SceneFile>().path = None;
+                SceneEditorState::default()
+            }
+            LoadScene::File(path) => match read_scene(world, &path) {
+                Ok(()) => {
+                    info!("Scene loaded from {}", path.display());
+                    let editor_state = read_editor_state(&path);
+                    world.resource_mut::<SceneFile>().path = Some(path);
+                    editor_state
+                }
+                Err(err) => {
+                    error!("Failed to load scene from {}: {err}", path.display());
+                    continue;
+                }
+            },
+        };
+
+        reset_editor_state(world);
+        world.insert_resource(editor_state);
+        world.send_event(SceneLoaded);
+    }
+}
+
+fn load_startup_scene(args: Res<EditorArgs>, mut load_scene: EventWriter<LoadScene>) {
+    if let Some(path) = &args.scene {
+        load_scene.send(LoadScene::File(path.clone()));
+    } else if let Some(count) = args.stress {
+        load_scene.send(LoadScene::Stress(count));
+    } else if args.sample {
+        load_scene.send(LoadScene::Sample);
+    }
+}
+
+pub struct SceneFilePlugin;
+
+impl Plugin for SceneFilePlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<SceneFile>()
+            .init_resource::<SceneEditorState>()
+            .add_event::<SaveScene>()
+            .add_event::<LoadScene>()
+            .add_event::<SceneLoaded>()
+            .add_systems(Startup, load_startup_scene)
+            // Loaded entities get their global transforms in the same frame
+            .add_systems(
+                PostUpdate,
+                (save_scene, load_scene)
+                    .chain()
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
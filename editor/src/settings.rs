@@ -0,0 +1,151 @@
+use std::error::Error;
+
+use bevy::{
+    app::{Plugin, PreUpdate, Update},
+    ecs::{
+        change_detection::DetectChanges,
+        schedule::IntoSystemConfigs,
+        system::{Res, Resource},
+    },
+    input::InputSystem,
+    log::warn,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::input_map::{track_navigation_drag, InputMap, NavigationDrag};
+
+/// Editor settings that outlive the session, stored in the working directory
+const SETTINGS_PATH: &str = "editor_settings.ron";
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SnapSettings {
+    pub enabled: bool,
+    pub translation_step: f32,
+    /// Rotation increment in degrees
+    pub rotation_step: f32,
+    pub scale_step: f32,
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        SnapSettings {
+            enabled: false,
+            translation_step: 0.5,
+            rotation_step: 15.,
+            scale_step: 0.1,
+        }
+    }
+}
+
+/// Snapping rounds the resulting values, not the change made to them,
+/// so the manipulator and the inspector put entities onto the same grid
+impl SnapSettings {
+    /// Holding the invert snapping binding inverts the toolbar toggle
+    pub fn is_active(&self, invert: bool) -> bool {
+        self.enabled != invert
+    }
+
+    pub fn translation(&self, value: f32) -> f32 {
+        snap(value, self.translation_step)
+    }
+
+    /// Takes and returns angle in radians
+    pub fn rotation(&self, angle: f32) -> f32 {
+        self.rotation_degrees(angle.to_degrees()).to_radians()
+    }
+
+    pub fn rotation_degrees(&self, angle: f32) -> f32 {
+        snap(angle, self.rotation_step)
+    }
+
+    /// Snapped scale never reaches zero, as entity could not be scaled back from it
+    pub fn scale(&self, value: f32) -> f32 {
+        let snapped = snap(value.abs(), self.scale_step).max(self.scale_step);
+        snapped.copysign(value)
+    }
+}
+
+fn snap(value: f32, step: f32) -> f32 {
+    if step <= 0. {
+        return value;
+    }
+
+    (value / step).round() * step
+}
+
+/// Space in which transforms are edited and manipulator axes are oriented
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TransformSpace {
+    #[default]
+    Local,
+    World,
+}
+
+#[derive(Resource, Default)]
+pub struct EditorSettings {
+    pub snap: SnapSettings,
+    pub transform_space: TransformSpace,
+    /// Debug option to list editor entities in the hierarchy and inspect them
+    pub show_editor_entities: bool,
+}
+
+/// Contents of the settings file
+#[derive(Serialize, Deserialize, Default)]
+struct SettingsFile {
+    #[serde(default)]
+    input: InputMap,
+}
+
+/**
+ * Missing file means the defaults, broken one is reported and replaced on the next save
+ */
+fn read_settings() -> SettingsFile {
+    let Ok(serialized) = std::fs::read_to_string(SETTINGS_PATH) else {
+        return SettingsFile::default();
+    };
+
+    let mut settings = ron::from_str::<SettingsFile>(&serialized).unwrap_or_else(|err| {
+        warn!("Failed to read settings from {SETTINGS_PATH}: {err}");
+        SettingsFile::default()
+    });
+    settings.input.fill_missing();
+    settings
+}
+
+fn write_settings(input: &InputMap) -> Result<(), Box<dyn Error>> {
+    let settings = SettingsFile {
+        input: input.clone(),
+    };
+    let serialized = ron::ser::to_string_pretty(&settings, ron::ser::PrettyConfig::default())?;
+
+    std::fs::write(SETTINGS_PATH, serialized)?;
+
+    Ok(())
+}
+
+/**
+ * Saves bindings as soon as they are edited
+ */
+fn save_settings(input_map: Res<InputMap>) {
+    if !input_map.is_changed() || input_map.is_added() {
+        return;
+    }
+
+    if let Err(err) = write_settings(&input_map) {
+        warn!("Failed to save settings to {SETTINGS_PATH}: {err}");
+    }
+}
+
+pub struct EditorSettingsPlugin;
+
+impl Plugin for EditorSettingsPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        let settings = read_settings();
+
+        app.init_resource::<EditorSettings>()
+            .init_resource::<NavigationDrag>()
+            .insert_resource(settings.input)
+            .add_systems(PreUpdate, track_navigation_drag.after(InputSystem))
+            .add_systems(Update, save_settings);
+    }
+}
@@ -0,0 +1,107 @@
+use std::any::TypeId;
+
+use bevy::{
+    ecs::{
+        entity::{Entity, EntityHashMap},
+        world::World,
+    },
+    hierarchy::{BuildWorldChildren, Children, Parent},
+    log::error,
+    reflect::TypeInfo,
+    scene::{DynamicScene, DynamicSceneBuilder},
+};
+use bevy_mod_picking::{Pickable, PickableBundle};
+
+/// Entity with all of its descendants, stored through reflection, so it can be copied
+pub struct EntitySnapshot {
+    entity: Entity,
+    parent: Option<Entity>,
+    /// Position among children of the parent
+    index: usize,
+    scene: DynamicScene,
+}
+
+impl EntitySnapshot {
+    pub fn take(world: &World, entity: Entity) -> Self {
+        let parent = world.get::<Parent>(entity).map(Parent::get);
+        let index = parent
+            .and_then(|parent| world.get::<Children>(parent))
+            .and_then(|children| children.iter().position(|child| *child == entity))
+            .unwrap_or_default();
+
+        let mut entities = vec![entity];
+        let mut index_to_visit = 0;
+        while let Some(visited) = entities.get(index_to_visit) {
+            if let Some(children) = world.get::<Children>(*visited) {
+                entities.extend(children.iter());
+            }
+            index_to_visit += 1;
+        }
+
+        let mut scene = DynamicSceneBuilder::from_world(world)
+            .extract_entities(entities.into_iter())
+            .build();
+
+        // Parent isn't part of the snapshot, copy is attached to it after being spawned
+        for dynamic_entity in &mut scene.entities {
+            if dynamic_entity.entity == entity {
+                dynamic_entity.components.retain(|component| {
+                    component.get_represented_type_info().map(TypeInfo::type_id)
+                        != Some(TypeId::of::<Parent>())
+                });
+            }
+        }
+
+        EntitySnapshot {
+            entity,
+            parent,
+            index,
+            scene,
+        }
+    }
+
+    /**
+     * Spawns a copy with new ids right after the original. Returns the root of the copy
+     */
+    pub fn spawn_copy(&self, world: &mut World) -> Entity {
+        let mut entity_map = EntityHashMap::default();
+        if let Err(err) = self.scene.write_to_world(world, &mut entity_map) {
+            error!("Failed to copy {:?}: {err}", self.entity);
+        }
+
+        let entity = *entity_map
+            .entry(self.entity)
+            .or_insert_with(|| world.spawn_empty().id());
+
+        if let Some(parent) = self
+            .parent
+            .filter(|parent| world.get_entity(*parent).is_some())
+        {
+            let len = world.get::<Children>(parent).map_or(0, |children| {
+                children.iter().filter(|child| **child != entity).count()
+            });
+            world
+                .entity_mut(parent)
+                .insert_children((self.index + 1).min(len), &[entity]);
+        }
+
+        make_pickable(world, entity_map.into_values());
+
+        entity
+    }
+}
+
+/**
+ * Picking markers are editor concern, so they might not be stored with the entity
+ */
+pub fn make_pickable(world: &mut World, entities: impl IntoIterator<Item = Entity>) {
+    for entity in entities {
+        let Some(mut entity) = world.get_entity_mut(entity) else {
+            continue;
+        };
+
+        if !entity.contains::<Pickable>() {
+            entity.insert(PickableBundle::default());
+        }
+    }
+}
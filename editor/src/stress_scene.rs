@@ -0,0 +1,30 @@
+use bevy::{
+    core::Name, ecs::world::World, hierarchy::BuildWorldChildren, render::prelude::SpatialBundle,
+};
+
+/// Entities in one group, including the group itself
+const GROUP_SIZE: usize = 100;
+
+/**
+ * Spawns given number of empty entities, grouped under roots like in big production scenes
+ */
+pub fn spawn(world: &mut World, count: usize) {
+    let mut spawned = 0;
+
+    while spawned < count {
+        let group_size = GROUP_SIZE.min(count - spawned);
+
+        world
+            .spawn((
+                SpatialBundle::default(),
+                Name::new(format!("Group {}", spawned / GROUP_SIZE)),
+            ))
+            .with_children(|parent| {
+                for index in 1..group_size {
+                    parent.spawn((SpatialBundle::default(), Name::new(format!("Item {index}"))));
+                }
+            });
+
+        spawned += group_size;
+    }
+}
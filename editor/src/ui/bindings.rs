@@ -0,0 +1,205 @@
+use bevy::{
+    ecs::system::{Res, ResMut, Resource},
+    input::{keyboard::KeyCode, mouse::MouseButton, ButtonInput},
+};
+use bevy_egui::{
+    egui::{self, Grid, RichText, ScrollArea},
+    EguiContexts,
+};
+
+use crate::input_map::{
+    Binding, InputAction, InputMap, InputModifiers, InputPreset, MODIFIER_KEYS,
+};
+
+/// Binding waiting for a key or a mouse button to be pressed
+struct Capture {
+    action: InputAction,
+    /// Binding being replaced, new one is added if there is none
+    index: Option<usize>,
+    /// Modifier pressed alone, which becomes the binding if it's released without another key
+    modifier: Option<KeyCode>,
+}
+
+#[derive(Resource, Default)]
+pub struct BindingsWindow {
+    pub open: bool,
+    capture: Option<Capture>,
+}
+
+/**
+ * Binding made of the pressed button and held modifiers, modifier keys can be bound by themselves
+ */
+fn captured_binding(
+    capture: &mut Capture,
+    keyboard: &ButtonInput<KeyCode>,
+    mouse: &ButtonInput<MouseButton>,
+) -> Option<Binding> {
+    let modifiers = InputModifiers::pressed(keyboard);
+
+    if let Some(key) = keyboard
+        .get_just_pressed()
+        .find(|key| !MODIFIER_KEYS.contains(key))
+    {
+        return Some(Binding::key(*key).with(modifiers));
+    }
+
+    if let Some(button) = mouse.get_just_pressed().next() {
+        return Some(Binding::mouse(*button).with(modifiers));
+    }
+
+    if let Some(key) = keyboard
+        .get_just_pressed()
+        .find(|key| MODIFIER_KEYS.contains(key))
+    {
+        capture.modifier = Some(*key);
+    }
+
+    capture
+        .modifier
+        .filter(|key| keyboard.just_released(*key))
+        .map(Binding::key)
+}
+
+pub fn draw(
+    mut contexts: EguiContexts,
+    mut bindings_window: ResMut<BindingsWindow>,
+    mut input_map: ResMut<InputMap>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+) {
+    if !bindings_window.open {
+        bindings_window.capture = None;
+        return;
+    }
+
+    // Editing a copy, so bindings are saved only when they actually change
+    let mut edited = input_map.clone();
+    let window = bindings_window.as_mut();
+
+    if let Some(capture) = &mut window.capture {
+        if keyboard.just_pressed(KeyCode::Escape) {
+            window.capture = None;
+        } else if let Some(binding) = captured_binding(capture, &keyboard, &mouse) {
+            let bindings = edited.bindings_mut(capture.action);
+            match capture.index.filter(|index| *index < bindings.len()) {
+                Some(index) => bindings[index] = binding,
+                None if !bindings.contains(&binding) => bindings.push(binding),
+                None => {}
+            }
+            window.capture = None;
+        }
+    }
+
+    let mut open = true;
+
+    egui::Window::new("Input Bindings")
+        .open(&mut open)
+        .default_width(380.)
+        .default_height(500.)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.menu_button("Load Preset", |ui| {
+                    for preset in InputPreset::ALL {
+                        if ui.button(preset.name()).clicked() {
+                            edited = preset.input_map();
+                            window.capture = None;
+                            ui.close_menu();
+                        }
+                    }
+                });
+                ui.label(RichText::new("Esc cancels rebinding").weak());
+            });
+            ui.separator();
+
+            ScrollArea::vertical().show(ui, |ui| {
+                for (group, actions) in InputAction::GROUPS {
+                    ui.label(RichText::new(group).strong());
+
+                    Grid::new(group)
+                        .num_columns(2)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for action in actions {
+                                ui.label(action.name());
+                                ui.horizontal_wrapped(|ui| {
+                                    draw_bindings(ui, *action, &mut edited, &mut window.capture);
+                                });
+                                ui.end_row();
+                            }
+                        });
+                    ui.add_space(10.);
+                }
+            });
+        });
+
+    if !open {
+        window.open = false;
+        window.capture = None;
+    }
+
+    if edited != *input_map {
+        *input_map = edited;
+    }
+}
+
+fn draw_bindings(
+    ui: &mut egui::Ui,
+    action: InputAction,
+    input_map: &mut InputMap,
+    capture: &mut Option<Capture>,
+) {
+    // Index of the binding being recorded for this action
+    let captured = capture
+        .as_ref()
+        .filter(|capture| capture.action == action)
+        .map(|capture| capture.index);
+    let capturing = |index| captured == Some(index);
+    let mut removed = None;
+
+    for (index, binding) in input_map.bindings(action).iter().enumerate() {
+        let text = if capturing(Some(index)) {
+            "Press a key...".to_owned()
+        } else {
+            binding.to_string()
+        };
+
+        let response = ui.button(text).on_hover_text("Click to rebind");
+        if capturing(Some(index)) {
+            // Focused widget makes other shortcuts ignore the keys
+            response.request_focus();
+        }
+        if response.clicked() {
+            *capture = Some(Capture {
+                action,
+                index: Some(index),
+                modifier: None,
+            });
+        }
+
+        if ui.small_button("✖").on_hover_text("Remove").clicked() {
+            removed = Some(index);
+        }
+    }
+
+    let text = if capturing(None) {
+        "Press a key..."
+    } else {
+        "+"
+    };
+    let response = ui.button(text).on_hover_text("Add binding");
+    if capturing(None) {
+        response.request_focus();
+    }
+    if response.clicked() {
+        *capture = Some(Capture {
+            action,
+            index: None,
+            modifier: None,
+        });
+    }
+
+    if let Some(index) = removed {
+        input_map.bindings_mut(action).remove(index);
+        *capture = None;
+    }
+}
@@ -1,5 +1,7 @@
 use bevy::ecs::system::Resource;
 
+/// Space taken by egui panels at each side of the window, in egui points.
+/// Camera viewport is the area left free
 #[derive(Default, Resource)]
 pub struct OccupiedScreenSpace {
     pub left: f32,
@@ -0,0 +1,163 @@
+use std::any::TypeId;
+
+use bevy::{core::Name, ecs::entity::Entity};
+use bevy_egui::egui::{self, Context, Ui};
+
+use crate::{
+    active_selection::ActiveSelection,
+    history::{EditorCommand, SpawnSource},
+    input_map::{EditorInput, InputAction, InputMap},
+};
+
+use super::drag_and_drop::HierarchyQueries;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HierarchyAction {
+    CreateEmpty,
+    CreateChild,
+    Duplicate,
+    Delete,
+    Rename,
+}
+
+fn menu_button(ui: &mut Ui, enabled: bool, text: &str, shortcut: Option<String>) -> bool {
+    let mut button = egui::Button::new(text);
+    if let Some(shortcut) = shortcut {
+        button = button.shortcut_text(shortcut);
+    }
+
+    let clicked = ui.add_enabled(enabled, button).clicked();
+    if clicked {
+        ui.close_menu();
+    }
+    clicked
+}
+
+/**
+ * Content of the right click menu, actions on the selection are disabled without one
+ */
+pub fn draw(
+    ui: &mut Ui,
+    active_selection: &ActiveSelection,
+    input_map: &InputMap,
+) -> Option<HierarchyAction> {
+    let has_selection = active_selection.active().is_some();
+    let mut action = None;
+
+    if menu_button(ui, true, "Create Empty", None) {
+        action = Some(HierarchyAction::CreateEmpty);
+    }
+    if menu_button(ui, has_selection, "Create Child", None) {
+        action = Some(HierarchyAction::CreateChild);
+    }
+
+    ui.separator();
+
+    if menu_button(
+        ui,
+        has_selection,
+        "Rename",
+        Some(input_map.shortcut_text(InputAction::Rename)),
+    ) {
+        action = Some(HierarchyAction::Rename);
+    }
+    if menu_button(
+        ui,
+        has_selection,
+        "Duplicate",
+        Some(input_map.shortcut_text(InputAction::Duplicate)),
+    ) {
+        action = Some(HierarchyAction::Duplicate);
+    }
+    if menu_button(
+        ui,
+        has_selection,
+        "Delete",
+        Some(input_map.shortcut_text(InputAction::Delete)),
+    ) {
+        action = Some(HierarchyAction::Delete);
+    }
+
+    action
+}
+
+pub fn shortcuts(ctx: &Context, input: &EditorInput) -> Option<HierarchyAction> {
+    // Text fields use these keys too
+    if ctx.wants_keyboard_input() {
+        return None;
+    }
+
+    if input.just_pressed(InputAction::Rename) {
+        Some(HierarchyAction::Rename)
+    } else if input.just_pressed(InputAction::Duplicate) {
+        Some(HierarchyAction::Duplicate)
+    } else if input.just_pressed(InputAction::Delete) {
+        Some(HierarchyAction::Delete)
+    } else {
+        None
+    }
+}
+
+/**
+ * Command for the action on current selection. Rename isn't a command until the new name is confirmed
+ */
+pub fn command(
+    action: HierarchyAction,
+    active_selection: &ActiveSelection,
+    queries: &HierarchyQueries,
+) -> Option<EditorCommand> {
+    // Descendants of selected entities are copied and deleted along with them
+    let entities = queries.topmost(active_selection.entities());
+
+    let command = match action {
+        HierarchyAction::CreateEmpty => EditorCommand::Spawn {
+            source: SpawnSource::Empty { parent: None },
+            spawned: Vec::new(),
+            deleted: Vec::new(),
+            selection: ActiveSelection::default(),
+        },
+        HierarchyAction::CreateChild => EditorCommand::Spawn {
+            source: SpawnSource::Empty {
+                parent: Some(active_selection.active()?),
+            },
+            spawned: Vec::new(),
+            deleted: Vec::new(),
+            selection: ActiveSelection::default(),
+        },
+        HierarchyAction::Duplicate if !entities.is_empty() => EditorCommand::Spawn {
+            source: SpawnSource::Duplicate(entities),
+            spawned: Vec::new(),
+            deleted: Vec::new(),
+            selection: ActiveSelection::default(),
+        },
+        HierarchyAction::Delete if !entities.is_empty() => EditorCommand::Despawn {
+            entities,
+            deleted: Vec::new(),
+            selection: ActiveSelection::default(),
+        },
+        _ => return None,
+    };
+
+    Some(command)
+}
+
+/**
+ * Renaming entity without a name gives it one
+ */
+pub fn rename_command(entity: Entity, name: String, queries: &HierarchyQueries) -> EditorCommand {
+    let name = Name::new(name);
+
+    match queries.names.get(entity) {
+        Ok(before) => EditorCommand::SetComponent {
+            entity,
+            type_id: TypeId::of::<Name>(),
+            name: "Name".to_owned(),
+            before: Box::new(before.clone()),
+            after: Box::new(name),
+        },
+        Err(_) => EditorCommand::AddComponents {
+            entity,
+            components: vec![(TypeId::of::<Name>(), "Name".to_owned(), Box::new(name))],
+        },
+    }
+}
@@ -0,0 +1,286 @@
+use bevy::{
+    core::Name,
+    ecs::{
+        entity::Entity,
+        system::{Query, SystemParam},
+    },
+    hierarchy::{Children, Parent},
+    render::view::{InheritedVisibility, Visibility},
+    transform::components::{GlobalTransform, Transform},
+};
+use bevy_egui::egui::{Rect, Stroke, Ui};
+
+use common::editor_components::RootOrder;
+
+use crate::{
+    history::{EditorCommand, HierarchyState},
+    lock::LockedEntities,
+};
+
+use super::rows::{root_entities, RootsQuery};
+
+/// Hierarchy components needed to edit the tree
+#[derive(SystemParam)]
+pub struct HierarchyQueries<'w, 's> {
+    pub parents: Query<'w, 's, &'static Parent>,
+    pub children: Query<'w, 's, &'static Children>,
+    pub transforms: Query<'w, 's, &'static Transform>,
+    pub global_transforms: Query<'w, 's, &'static GlobalTransform>,
+    pub names: Query<'w, 's, &'static Name>,
+    pub visibilities: Query<'w, 's, (&'static Visibility, &'static InheritedVisibility)>,
+    pub locked: LockedEntities<'w, 's>,
+    pub roots: RootsQuery<'w, 's>,
+    pub root_orders: Query<'w, 's, &'static RootOrder>,
+}
+
+impl HierarchyQueries<'_, '_> {
+    pub fn parent(&self, entity: Entity) -> Option<Entity> {
+        self.parents.get(entity).ok().map(Parent::get)
+    }
+
+    /**
+     * True if entity is one of the given ones, or a descendant of one of them
+     */
+    pub fn is_within(&self, entity: Entity, ancestors: &[Entity]) -> bool {
+        let mut current = Some(entity);
+
+        while let Some(entity) = current {
+            if ancestors.contains(&entity) {
+                return true;
+            }
+            current = self.parent(entity);
+        }
+
+        false
+    }
+
+    /**
+     * Leaves out entities, whose ancestor is among the given ones
+     */
+    pub fn topmost(&self, entities: &[Entity]) -> Vec<Entity> {
+        entities
+            .iter()
+            .filter(|entity| {
+                !self
+                    .parent(**entity)
+                    .is_some_and(|parent| self.is_within(parent, entities))
+            })
+            .copied()
+            .collect()
+    }
+
+    /**
+     * Children of the parent or the roots in their order, without the excluded entities
+     */
+    fn siblings_without(&self, parent: Option<Entity>, excluded: &[Entity]) -> Vec<Entity> {
+        match parent {
+            Some(parent) => self.children_without(parent, excluded),
+            None => root_entities(&self.roots, false)
+                .into_iter()
+                .filter(|root| !excluded.contains(root))
+                .collect(),
+        }
+    }
+
+    fn children_without(&self, parent: Entity, excluded: &[Entity]) -> Vec<Entity> {
+        self.children
+            .get(parent)
+            .map(|children| {
+                children
+                    .iter()
+                    .filter(|child| !excluded.contains(child))
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Where dragged entities are dropped, relative to the row under the pointer
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DropTarget {
+    Before(Entity),
+    After(Entity),
+    Into(Entity),
+    /// Empty space below the tree, entities become roots
+    Root,
+}
+
+/// Resolved drop: new parent and position among its children
+struct Placement {
+    parent: Option<Entity>,
+    /// Index among children, that aren't being moved
+    index: usize,
+    moved: Vec<Entity>,
+}
+
+impl DropTarget {
+    /**
+     * Top and bottom quarters of the row put entities next to it, the middle puts them inside
+     */
+    pub fn from_pointer(entity: Entity, rect: Rect, pointer_y: f32) -> Self {
+        let position = (pointer_y - rect.top()) / rect.height();
+
+        if position < 0.25 {
+            DropTarget::Before(entity)
+        } else if position > 0.75 {
+            DropTarget::After(entity)
+        } else {
+            DropTarget::Into(entity)
+        }
+    }
+
+    pub fn paint(&self, ui: &Ui, rect: Rect, valid: bool) {
+        let color = if valid {
+            ui.visuals().selection.stroke.color
+        } else {
+            ui.visuals().error_fg_color
+        };
+        let stroke = Stroke::new(2., color);
+
+        match self {
+            DropTarget::Before(_) => {
+                ui.painter().hline(rect.x_range(), rect.top(), stroke);
+            }
+            DropTarget::After(_) => {
+                ui.painter().hline(rect.x_range(), rect.bottom(), stroke);
+            }
+            DropTarget::Into(_) | DropTarget::Root => {
+                ui.painter().rect_stroke(rect, 2., stroke);
+            }
+        }
+    }
+
+    fn placement(&self, dragged: &[Entity], queries: &HierarchyQueries) -> Option<Placement> {
+        // Entities dragged along with their ancestor just follow it
+        let moved = queries.topmost(dragged);
+
+        let (parent, sibling) = match *self {
+            DropTarget::Root => (None, None),
+            DropTarget::Into(entity) => (Some(entity), None),
+            DropTarget::Before(entity) => (queries.parent(entity), Some((entity, 0))),
+            DropTarget::After(entity) => (queries.parent(entity), Some((entity, 1))),
+        };
+
+        // Entity can't become a child of itself or its own descendant
+        if parent.is_some_and(|parent| queries.is_within(parent, &moved)) {
+            return None;
+        }
+
+        let siblings = queries.siblings_without(parent, &moved);
+
+        let index = match sibling {
+            // Dropping next to a moved entity keeps it where it is
+            Some((sibling, _)) if moved.contains(&sibling) => return None,
+            Some((sibling, offset)) => {
+                siblings.iter().position(|child| *child == sibling)? + offset
+            }
+            None => siblings.len(),
+        };
+
+        Some(Placement {
+            parent,
+            index,
+            moved,
+        })
+    }
+
+    pub fn is_valid(&self, dragged: &[Entity], queries: &HierarchyQueries) -> bool {
+        self.placement(dragged, queries).is_some()
+    }
+
+    /**
+     * Command moving dragged entities to this target, keeping their world transforms.
+     * None if the drop is invalid or doesn't change anything
+     */
+    pub fn command(&self, dragged: &[Entity], queries: &HierarchyQueries) -> Option<EditorCommand> {
+        let Placement {
+            parent,
+            index,
+            moved,
+        } = self.placement(dragged, queries)?;
+
+        let mut before = HierarchyState::default();
+        let mut after = HierarchyState::default();
+
+        // Every parent, that loses or gets children
+        let mut affected = moved
+            .iter()
+            .filter_map(|entity| queries.parent(*entity))
+            .chain(parent)
+            .collect::<Vec<_>>();
+        affected.sort();
+        affected.dedup();
+
+        for affected in affected {
+            before
+                .children
+                .push((affected, queries.children_without(affected, &[])));
+
+            let mut children = queries.children_without(affected, &moved);
+            if Some(affected) == parent {
+                children.splice(index..index, moved.iter().copied());
+            }
+            after.children.push((affected, children));
+        }
+
+        let parent_transform = parent.and_then(|parent| queries.global_transforms.get(parent).ok());
+
+        for entity in &moved {
+            before.parents.push((*entity, queries.parent(*entity)));
+            after.parents.push((*entity, parent));
+
+            let (Ok(transform), Ok(global_transform)) = (
+                queries.transforms.get(*entity),
+                queries.global_transforms.get(*entity),
+            ) else {
+                continue;
+            };
+
+            before.transforms.push((*entity, *transform));
+            after.transforms.push((
+                *entity,
+                match parent_transform {
+                    Some(parent_transform) => global_transform.reparented_to(parent_transform),
+                    None => global_transform.compute_transform(),
+                },
+            ));
+        }
+
+        // Roots are numbered in their new order, so it's kept in the saved scene
+        if parent.is_none() {
+            let mut roots = queries.siblings_without(None, &moved);
+            roots.splice(index..index, moved.iter().copied());
+
+            for (order, root) in roots.into_iter().enumerate() {
+                let order = RootOrder(order as u32);
+                let current = queries.root_orders.get(root).ok().copied();
+
+                if current != Some(order) {
+                    before.root_orders.push((root, current));
+                    after.root_orders.push((root, Some(order)));
+                }
+            }
+        } else {
+            // Children follow the order of their parent, so the root order is dropped
+            for entity in &moved {
+                if let Ok(order) = queries.root_orders.get(*entity) {
+                    before.root_orders.push((*entity, Some(*order)));
+                    after.root_orders.push((*entity, None));
+                }
+            }
+        }
+
+        if before.parents == after.parents
+            && before.children == after.children
+            && before.root_orders == after.root_orders
+        {
+            return None;
+        }
+
+        Some(EditorCommand::SetParent {
+            before: Box::new(before),
+            after: Box::new(after),
+        })
+    }
+}
@@ -0,0 +1,122 @@
+use crate::scene_file::{SaveScene, SceneEditorState, SceneLoaded};
+use bevy::{
+    core::Name,
+    ecs::{
+        entity::EntityHashMap,
+        event::EventReader,
+        system::{Commands, Query, Res, ResMut},
+    },
+    hierarchy::Children,
+    utils::{HashMap, HashSet},
+};
+use common::editor_components::RootOrder;
+
+use super::{
+    rows::{root_entities, RootsQuery},
+    HierarchyPanel,
+};
+
+/**
+ * Path of names from the root, which stays the same between sessions unlike entity ids.
+ * Siblings with the same name are told apart by their order
+ */
+fn node_keys(
+    roots: &RootsQuery,
+    children: &Query<&Children>,
+    names: &Query<&Name>,
+) -> EntityHashMap<String> {
+    let mut keys = EntityHashMap::default();
+    let mut to_visit = vec![(String::new(), root_entities(roots, false))];
+
+    while let Some((parent_key, siblings)) = to_visit.pop() {
+        let mut occurrences = HashMap::<&str, usize>::default();
+
+        for entity in siblings {
+            let name = names.get(entity).map_or(super::UNNAMED, Name::as_str);
+            let occurrence = occurrences.entry(name).or_default();
+            let key = format!("{parent_key}/{name}#{occurrence}");
+            *occurrence += 1;
+
+            if let Ok(children) = children.get(entity) {
+                to_visit.push((key.clone(), children.to_vec()));
+            }
+            keys.insert(entity, key);
+        }
+    }
+
+    keys
+}
+
+/**
+ * Puts expanded nodes into the editor state, right before it's saved along with the scene.
+ * Roots without a stored order get one, so their keys match after loading
+ */
+pub fn store_expanded(
+    mut commands: Commands,
+    mut save_scene: EventReader<SaveScene>,
+    panel: Res<HierarchyPanel>,
+    roots: RootsQuery,
+    children: Query<&Children>,
+    names: Query<&Name>,
+    mut editor_state: ResMut<SceneEditorState>,
+) {
+    if save_scene.is_empty() {
+        return;
+    }
+    save_scene.clear();
+
+    // Unordered roots are listed last, so numbering them after the rest keeps the order.
+    // Editor entities aren't part of the scene and are left as they are
+    let mut next_order = roots
+        .iter()
+        .filter(|(_, _, editor_only)| !editor_only)
+        .filter_map(|(_, order, _)| order.map(|order| order.0 + 1))
+        .max()
+        .unwrap_or_default();
+    for root in root_entities(&roots, false) {
+        if roots.get(root).is_ok_and(|(_, order, _)| order.is_none()) {
+            commands.entity(root).insert(RootOrder(next_order));
+            next_order += 1;
+        }
+    }
+
+    let keys = node_keys(&roots, &children, &names);
+
+    editor_state.expanded = panel
+        .expanded
+        .iter()
+        .filter_map(|entity| keys.get(entity).cloned())
+        .collect();
+    editor_state.expanded.sort();
+}
+
+/**
+ * Expands nodes stored in the editor state of the loaded scene, everything else stays collapsed
+ */
+pub fn restore_expanded(
+    mut scene_loaded: EventReader<SceneLoaded>,
+    mut panel: ResMut<HierarchyPanel>,
+    roots: RootsQuery,
+    children: Query<&Children>,
+    names: Query<&Name>,
+    editor_state: Res<SceneEditorState>,
+) {
+    if scene_loaded.is_empty() {
+        return;
+    }
+    scene_loaded.clear();
+
+    let keys = node_keys(&roots, &children, &names);
+    let expanded = editor_state
+        .expanded
+        .iter()
+        .map(String::as_str)
+        .collect::<HashSet<_>>();
+
+    panel.expanded = keys
+        .into_iter()
+        .filter(|(_, key)| expanded.contains(key.as_str()))
+        .map(|(entity, _)| entity)
+        .collect();
+    panel.rows.mark_outdated();
+}
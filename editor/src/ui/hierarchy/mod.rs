@@ -0,0 +1,440 @@
+mod context_menu;
+mod drag_and_drop;
+mod expanded;
+mod rows;
+mod search;
+mod toggles;
+
+use std::time::Instant;
+
+use bevy::{
+    core::Name,
+    diagnostic::{DiagnosticPath, Diagnostics},
+    ecs::{
+        change_detection::DetectChanges,
+        entity::{Entity, EntityHashSet},
+        event::EventWriter,
+        system::{Res, ResMut, Resource},
+    },
+};
+use bevy_egui::{
+    egui::{
+        self, collapsing_header::paint_default_icon, FontId, Key, Response, RichText, Sense, Ui,
+        Vec2,
+    },
+    EguiContexts,
+};
+
+use crate::{
+    active_selection::ActiveSelection,
+    camera::FrameSelection,
+    history::{EditorCommand, History},
+    input_map::{EditorInput, InputAction, InputMap},
+    settings::EditorSettings,
+    ui::editor_screen_space::OccupiedScreenSpace,
+};
+
+pub use self::expanded::{restore_expanded, store_expanded};
+
+use self::{
+    context_menu::HierarchyAction,
+    drag_and_drop::{DropTarget, HierarchyQueries},
+    rows::{HierarchyChanges, HierarchyRow, HierarchyRows},
+    search::{EntityComponents, HierarchySearch},
+};
+
+/// Time spent on the panel each frame
+pub const DRAW_TIME: DiagnosticPath = DiagnosticPath::const_new("editor/hierarchy/draw_time");
+/// Time spent on flattening the tree, when it changes
+pub const REBUILD_TIME: DiagnosticPath = DiagnosticPath::const_new("editor/hierarchy/rebuild_time");
+
+const UNNAMED: &str = "Unnamed";
+const ROW_TEXT_SIZE: f32 = 18.;
+
+/// State of the panel kept between frames
+#[derive(Resource, Default)]
+pub struct HierarchyPanel {
+    /// Entity being renamed and its edited name
+    renaming: Option<(Entity, String)>,
+    /// Nodes showing their children, the rest are collapsed
+    expanded: EntityHashSet,
+    /// Text of the search box
+    search: String,
+    rows: HierarchyRows,
+}
+
+/// State collected while walking over the tree
+struct TreeContext<'a> {
+    active_selection: &'a ActiveSelection,
+    input_map: &'a InputMap,
+    queries: &'a HierarchyQueries<'a, 'a>,
+    renaming: &'a mut Option<(Entity, String)>,
+    /// Node, which arrow was clicked
+    toggled: Option<Entity>,
+    /// Visibility or lock change from the row toggles
+    command: Option<EditorCommand>,
+    clicked: Option<Entity>,
+    /// Row was double clicked to frame it in the viewport
+    framed: bool,
+    /// Entities being dragged and where they were released
+    dropped: Option<(Vec<Entity>, DropTarget)>,
+    action: Option<HierarchyAction>,
+    /// Entity and its new name, once renaming is confirmed
+    renamed: Option<(Entity, String)>,
+}
+
+impl TreeContext<'_> {
+    fn row(&mut self, ui: &mut Ui, row: &HierarchyRow, row_height: f32) {
+        let entity = row.entity;
+        let is_entity_selected = self.active_selection.is_selected(entity);
+
+        ui.set_height(row_height);
+
+        if let Some(command) = toggles::draw(ui, entity, self.queries) {
+            self.command = Some(command);
+        }
+
+        ui.add_space(row.depth as f32 * ui.spacing().indent);
+
+        // Same arrow as collapsing headers, rows without children keep the space to stay aligned
+        let (_, toggle) =
+            ui.allocate_exact_size(Vec2::splat(ui.spacing().icon_width), Sense::click());
+        if row.has_children {
+            paint_default_icon(ui, if row.is_expanded { 1. } else { 0. }, &toggle);
+            if toggle.clicked() {
+                self.toggled = Some(entity);
+            }
+        }
+
+        if let Some((_, name)) = self
+            .renaming
+            .as_mut()
+            .filter(|(renamed, _)| *renamed == entity)
+        {
+            let response = ui.text_edit_singleline(name);
+
+            if !response.lost_focus() {
+                response.request_focus();
+                return;
+            }
+
+            // Escape cancels renaming, anything else that takes the focus away confirms it
+            if !ui.input(|input| input.key_pressed(Key::Escape)) {
+                self.renamed = self.renaming.take();
+            }
+            *self.renaming = None;
+            return;
+        }
+
+        let name = unpack_name(self.queries.names.get(entity).ok());
+        let mut heading = ui.add(row_label(RichText::new(name).size(ROW_TEXT_SIZE)));
+
+        if is_entity_selected {
+            heading = heading.highlight();
+        }
+
+        // Right click on entity outside of selection makes menu act on it
+        if heading.clicked() || (heading.secondary_clicked() && !is_entity_selected) {
+            self.clicked = Some(entity);
+        }
+
+        // First click of the two has already selected the entity
+        if heading.double_clicked() {
+            self.framed = true;
+        }
+
+        heading.context_menu(|ui| {
+            if let Some(action) = context_menu::draw(ui, self.active_selection, self.input_map) {
+                self.action = Some(action);
+            }
+        });
+
+        self.drag_and_drop(ui, &heading, entity);
+    }
+
+    /**
+     * Makes the row draggable and a target for other dragged rows
+     */
+    fn drag_and_drop(&mut self, ui: &Ui, response: &Response, entity: Entity) {
+        // Dragging selected entity moves the whole selection
+        if response.drag_started() {
+            let dragged = if self.active_selection.is_selected(entity) {
+                self.active_selection.entities().to_vec()
+            } else {
+                vec![entity]
+            };
+            response.dnd_set_drag_payload(dragged);
+        }
+
+        let Some(pointer) = ui.input(|input| input.pointer.interact_pos()) else {
+            return;
+        };
+
+        let target = DropTarget::from_pointer(entity, response.rect, pointer.y);
+
+        if let Some(dragged) = response.dnd_hover_payload::<Vec<Entity>>() {
+            target.paint(ui, response.rect, target.is_valid(&dragged, self.queries));
+        }
+
+        if let Some(dragged) = response.dnd_release_payload::<Vec<Entity>>() {
+            self.dropped = Some((dragged.to_vec(), target));
+        }
+    }
+}
+
+pub fn draw(
+    mut contexts: EguiContexts,
+    input: EditorInput,
+    hierarchy_queries: HierarchyQueries,
+    entity_components: EntityComponents,
+    mut hierarchy_changes: HierarchyChanges,
+    active_selection: Res<ActiveSelection>,
+    settings: Res<EditorSettings>,
+    mut panel: ResMut<HierarchyPanel>,
+    mut history: ResMut<History>,
+    mut frame_selection: EventWriter<FrameSelection>,
+    mut occupied_screen_space: ResMut<OccupiedScreenSpace>,
+    mut diagnostics: Diagnostics,
+) {
+    let started = Instant::now();
+    let history_changed = history.is_changed();
+
+    let ctx = contexts.ctx_mut();
+    let shortcut_action = context_menu::shortcuts(ctx, &input);
+
+    let width = egui::SidePanel::left("Hierarchy")
+        .resizable(true)
+        .min_width(200.)
+        .show(ctx, |ui| {
+            ui.add_space(10.);
+            ui.heading(RichText::new("Hierarchy").size(30.));
+            ui.add_space(5.);
+
+            let panel = panel.as_mut();
+
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut panel.search)
+                        .hint_text("Search, t:Type for components")
+                        .desired_width(ui.available_width() - 30.),
+                );
+                if ui
+                    .add_enabled(!panel.search.is_empty(), egui::Button::new("✖"))
+                    .clicked()
+                {
+                    panel.search.clear();
+                }
+            });
+            ui.add_space(5.);
+
+            let rebuild_started = Instant::now();
+            if panel.rows.update(
+                &mut hierarchy_changes,
+                history_changed,
+                &hierarchy_queries,
+                &entity_components,
+                &mut panel.expanded,
+                &panel.search,
+                settings.show_editor_entities,
+            ) {
+                diagnostics.add_measurement(&REBUILD_TIME, || {
+                    rebuild_started.elapsed().as_secs_f64() * 1000.
+                });
+            }
+
+            let mut tree = TreeContext {
+                active_selection: &active_selection,
+                input_map: &input.map,
+                queries: &hierarchy_queries,
+                renaming: &mut panel.renaming,
+                toggled: None,
+                command: None,
+                clicked: None,
+                framed: false,
+                dropped: None,
+                action: shortcut_action,
+                renamed: None,
+            };
+
+            draw_rows(ui, &panel.rows.rows, &mut tree);
+
+            // Empty space below the tree takes entities out of their parents
+            let root_area = ui.allocate_rect(ui.available_rect_before_wrap(), Sense::click());
+            root_area.context_menu(|ui| {
+                if let Some(action) = context_menu::draw(ui, &active_selection, &input.map) {
+                    tree.action = Some(action);
+                }
+            });
+            if let Some(dragged) = root_area.dnd_hover_payload::<Vec<Entity>>() {
+                DropTarget::Root.paint(
+                    ui,
+                    root_area.rect,
+                    DropTarget::Root.is_valid(&dragged, &hierarchy_queries),
+                );
+            }
+            if let Some(dragged) = root_area.dnd_release_payload::<Vec<Entity>>() {
+                tree.dropped = Some((dragged.to_vec(), DropTarget::Root));
+            }
+
+            let TreeContext {
+                toggled,
+                command,
+                clicked,
+                framed,
+                dropped,
+                action,
+                renamed,
+                ..
+            } = tree;
+
+            if let Some((dragged, target)) = dropped {
+                if let Some(command) = target.command(&dragged, &hierarchy_queries) {
+                    history.push(command);
+
+                    // Dropped entities stay in sight
+                    if let DropTarget::Into(parent) = target {
+                        panel.expanded.insert(parent);
+                        panel.rows.mark_outdated();
+                    }
+                }
+            }
+
+            if let Some(command) = command {
+                history.push(command);
+            }
+
+            if let Some((entity, name)) = renamed {
+                history.push(context_menu::rename_command(
+                    entity,
+                    name,
+                    &hierarchy_queries,
+                ));
+            }
+
+            match action {
+                Some(HierarchyAction::Rename) => {
+                    if let Some(entity) = active_selection.active() {
+                        let name = hierarchy_queries
+                            .names
+                            .get(entity)
+                            .map_or_else(|_| String::new(), |name| name.as_str().to_owned());
+                        panel.renaming = Some((entity, name));
+                    }
+                }
+                Some(action) => {
+                    if let Some(command) =
+                        context_menu::command(action, &active_selection, &hierarchy_queries)
+                    {
+                        history.push(command);
+                    }
+                }
+                None => {}
+            }
+
+            // Search results stay expanded
+            if let Some(toggled) =
+                toggled.filter(|_| HierarchySearch::parse(&panel.search).is_none())
+            {
+                if !panel.expanded.remove(&toggled) {
+                    panel.expanded.insert(toggled);
+                }
+                panel.rows.mark_outdated();
+            }
+
+            if let Some(clicked) = clicked {
+                let order = panel.rows.entities();
+                let add = input.held(InputAction::AddToSelection);
+                let toggle = input.held(InputAction::ToggleSelection);
+                history.change_selection(&active_selection, |active_selection| {
+                    change_selection(clicked, &order, add, toggle, active_selection);
+                });
+            }
+
+            if framed {
+                frame_selection.send_default();
+            }
+        })
+        .response
+        .rect
+        .width();
+
+    if occupied_screen_space.left != width {
+        occupied_screen_space.left = width;
+    }
+
+    diagnostics.add_measurement(&DRAW_TIME, || started.elapsed().as_secs_f64() * 1000.);
+}
+
+/**
+ * Lays out only the rows scrolled into view, rows have the same height for that
+ */
+fn draw_rows(ui: &mut Ui, rows: &[HierarchyRow], tree: &mut TreeContext) {
+    let row_height = ui
+        .fonts(|fonts| fonts.row_height(&FontId::proportional(ROW_TEXT_SIZE)))
+        .max(ui.spacing().interact_size.y);
+    // Empty space below the tree is left to drop entities there
+    let max_height = (ui.available_height() - row_height * 2.).max(row_height);
+
+    egui::ScrollArea::vertical()
+        .auto_shrink([false, true])
+        .max_height(max_height)
+        .show_rows(ui, row_height, rows.len(), |ui, range| {
+            for row in &rows[range] {
+                // Ids stay with entities, while rows scroll in and out of view
+                ui.push_id(row.entity, |ui| {
+                    ui.horizontal(|ui| tree.row(ui, row, row_height));
+                });
+            }
+        });
+}
+
+/**
+ * Text of the row can't be selected, so dragging it moves the entity instead
+ */
+fn row_label(text: RichText) -> egui::Label {
+    egui::Label::new(text)
+        .selectable(false)
+        .sense(Sense::click_and_drag())
+}
+
+/**
+ * Toggle binding toggles clicked entity, add binding selects everything between active entity
+ * and clicked one
+ */
+fn change_selection(
+    entity: Entity,
+    order: &[Entity],
+    add: bool,
+    toggle: bool,
+    active_selection: &mut ActiveSelection,
+) {
+    let anchor = active_selection.active();
+
+    let range = anchor.and_then(|anchor| {
+        let from = order.iter().position(|visited| *visited == anchor)?;
+        let to = order.iter().position(|visited| *visited == entity)?;
+        Some((anchor, from.min(to)..=from.max(to)))
+    });
+
+    match range {
+        Some((anchor, range)) if add => {
+            if !toggle {
+                active_selection.clear();
+            }
+
+            for visited in &order[range] {
+                active_selection.add(*visited);
+            }
+
+            // Anchor stays active, so next range starts from the same place
+            active_selection.add(anchor);
+        }
+        _ if toggle => active_selection.toggle(entity),
+        _ if add => active_selection.add(entity),
+        _ => active_selection.select(entity),
+    }
+}
+
+fn unpack_name(name: Option<&Name>) -> &str {
+    name.map_or(UNNAMED, |name| name.as_str())
+}
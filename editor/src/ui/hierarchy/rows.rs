@@ -0,0 +1,359 @@
+use bevy::{
+    core::Name,
+    ecs::{
+        entity::{Entities, Entity, EntityHashSet},
+        query::{Added, Changed, Has, Without},
+        removal_detection::RemovedComponents,
+        system::{Query, SystemParam},
+    },
+    hierarchy::{Children, Parent},
+};
+use common::editor_components::{EditorOnly, RootOrder};
+
+use crate::deleted::Deleted;
+
+use super::{
+    drag_and_drop::HierarchyQueries,
+    search::{EntityComponents, HierarchySearch},
+};
+
+pub type RootsQuery<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, Option<&'static RootOrder>, Has<EditorOnly>),
+    (Without<Parent>, Without<Deleted>),
+>;
+
+/**
+ * Entities at the top of the tree in their stored order, the ones without it go last.
+ * Editor entities are left out unless asked for
+ */
+pub fn root_entities(roots: &RootsQuery, show_editor_entities: bool) -> Vec<Entity> {
+    let mut ordered = roots
+        .iter()
+        .filter(|(_, _, editor_only)| show_editor_entities || !editor_only)
+        .map(|(entity, order, _)| (order.map_or(u32::MAX, |order| order.0), entity))
+        .collect::<Vec<_>>();
+    ordered.sort_unstable();
+
+    ordered.into_iter().map(|(_, entity)| entity).collect()
+}
+
+/// Row of the flattened tree
+#[derive(Clone, Copy)]
+pub struct HierarchyRow {
+    pub entity: Entity,
+    pub depth: usize,
+    pub has_children: bool,
+    pub is_expanded: bool,
+}
+
+/// Everything that makes the flattened tree outdated, besides the panel itself
+#[derive(SystemParam)]
+pub struct HierarchyChanges<'w, 's> {
+    entities: &'w Entities,
+    parents: Query<'w, 's, (), Changed<Parent>>,
+    children: Query<'w, 's, (), Changed<Children>>,
+    names: Query<'w, 's, (), Changed<Name>>,
+    root_orders: Query<'w, 's, (), Changed<RootOrder>>,
+    deleted: Query<'w, 's, (), Added<Deleted>>,
+    removed_parents: RemovedComponents<'w, 's, Parent>,
+    removed_children: RemovedComponents<'w, 's, Children>,
+    removed_names: RemovedComponents<'w, 's, Name>,
+    removed_root_orders: RemovedComponents<'w, 's, RootOrder>,
+    restored: RemovedComponents<'w, 's, Deleted>,
+}
+
+impl HierarchyChanges<'_, '_> {
+    /**
+     * Spawned and despawned entities are caught by the entity count
+     */
+    fn any(&mut self, entity_count: &mut u32) -> bool {
+        let removed = !self.removed_parents.is_empty()
+            || !self.removed_children.is_empty()
+            || !self.removed_names.is_empty()
+            || !self.removed_root_orders.is_empty()
+            || !self.restored.is_empty();
+        self.removed_parents.clear();
+        self.removed_children.clear();
+        self.removed_names.clear();
+        self.removed_root_orders.clear();
+        self.restored.clear();
+
+        let count_changed = *entity_count != self.entities.len();
+        *entity_count = self.entities.len();
+
+        removed
+            || count_changed
+            || !self.parents.is_empty()
+            || !self.children.is_empty()
+            || !self.names.is_empty()
+            || !self.root_orders.is_empty()
+            || !self.deleted.is_empty()
+    }
+}
+
+/// Tree flattened into rows, so only the visible ones are laid out.
+/// Rebuilt only when the hierarchy, expanded nodes or the search change
+#[derive(Default)]
+pub struct HierarchyRows {
+    pub rows: Vec<HierarchyRow>,
+    outdated: bool,
+    entity_count: u32,
+    /// Search the rows were built with
+    search: String,
+    show_editor_entities: bool,
+}
+
+impl HierarchyRows {
+    pub fn mark_outdated(&mut self) {
+        self.outdated = true;
+    }
+
+    /**
+     * Rebuilds rows if anything has changed, returns true if it did.
+     * Despawned entities are forgotten from the expanded ones on the way.
+     * Component types aren't tracked, so component filters refresh along with the edit history
+     */
+    pub fn update(
+        &mut self,
+        changes: &mut HierarchyChanges,
+        history_changed: bool,
+        queries: &HierarchyQueries,
+        entity_components: &EntityComponents,
+        expanded: &mut EntityHashSet,
+        search_text: &str,
+        show_editor_entities: bool,
+    ) -> bool {
+        let search = HierarchySearch::parse(search_text);
+
+        let changed = changes.any(&mut self.entity_count)
+            || self.search != search_text
+            || self.show_editor_entities != show_editor_entities
+            || (history_changed && search.as_ref().is_some_and(HierarchySearch::has_components));
+
+        if !changed && !self.outdated {
+            return false;
+        }
+
+        self.outdated = false;
+        expanded.retain(|entity| changes.entities.contains(*entity));
+        self.search = search_text.to_owned();
+        self.show_editor_entities = show_editor_entities;
+        self.rows.clear();
+
+        // Search results are shown expanded, without changing what user has expanded
+        let visible = search.map(|search| {
+            search.visible_entities(
+                root_entities(&queries.roots, show_editor_entities),
+                queries,
+                entity_components,
+            )
+        });
+        let is_visible = |entity: &Entity| {
+            visible
+                .as_ref()
+                .is_none_or(|visible| visible.contains(entity))
+        };
+
+        let mut to_visit = root_entities(&queries.roots, show_editor_entities)
+            .into_iter()
+            .filter(is_visible)
+            .map(|entity| (entity, 0))
+            .collect::<Vec<_>>();
+        // Stack takes the last one first
+        to_visit.reverse();
+
+        while let Some((entity, depth)) = to_visit.pop() {
+            let children = queries
+                .children
+                .get(entity)
+                .map(|children| children.iter().filter(|child| is_visible(child)))
+                .ok();
+            let has_children = children
+                .clone()
+                .is_some_and(|mut children| children.next().is_some());
+            let is_expanded = visible.is_some() || expanded.contains(&entity);
+
+            self.rows.push(HierarchyRow {
+                entity,
+                depth,
+                has_children,
+                is_expanded,
+            });
+
+            if let Some(children) = children.filter(|_| is_expanded) {
+                let start = to_visit.len();
+                to_visit.extend(children.map(|child| (*child, depth + 1)));
+                to_visit[start..].reverse();
+            }
+        }
+
+        true
+    }
+
+    pub fn entities(&self) -> Vec<Entity> {
+        self.rows.iter().map(|row| row.entity).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use bevy::{
+        app::App,
+        ecs::{entity::EntityHashSet, system::SystemState, world::World},
+        MinimalPlugins,
+    };
+    use bevy_egui::egui::{CentralPanel, Context, RawInput};
+
+    use crate::{active_selection::ActiveSelection, input_map::InputMap, stress_scene};
+
+    use super::{
+        super::{draw_rows, TreeContext},
+        EntityComponents, HierarchyChanges, HierarchyQueries, HierarchyRows,
+    };
+
+    const ENTITY_COUNT: usize = 100_000;
+    /// Flattening the whole tree may take a few frames, it only happens on structural changes
+    const REBUILD_BUDGET: Duration = Duration::from_millis(50);
+    /// Frame without changes has to leave most of 60 FPS frame for the rest of the editor
+    const UNCHANGED_FRAME_BUDGET: Duration = Duration::from_millis(4);
+
+    type UpdateState = SystemState<(
+        HierarchyChanges<'static, 'static>,
+        HierarchyQueries<'static, 'static>,
+        EntityComponents<'static>,
+    )>;
+
+    fn timed_update(
+        state: &mut UpdateState,
+        world: &mut World,
+        rows: &mut HierarchyRows,
+        expanded: &mut EntityHashSet,
+    ) -> (bool, Duration) {
+        let (mut changes, queries, components) = state.get_mut(world);
+
+        let started = Instant::now();
+        let rebuilt = rows.update(
+            &mut changes,
+            false,
+            &queries,
+            &components,
+            expanded,
+            "",
+            false,
+        );
+        (rebuilt, started.elapsed())
+    }
+
+    /**
+     * Rows are laid out the way the panel does it, only the ones scrolled into view
+     */
+    fn timed_draw(
+        state: &mut UpdateState,
+        world: &mut World,
+        ctx: &Context,
+        rows: &HierarchyRows,
+    ) -> Duration {
+        let (_, queries, _) = state.get(world);
+        let active_selection = ActiveSelection::default();
+        let input_map = InputMap::default();
+        let mut renaming = None;
+
+        let started = Instant::now();
+        let _ = ctx.run(RawInput::default(), |ctx| {
+            CentralPanel::default().show(ctx, |ui| {
+                let mut tree = TreeContext {
+                    active_selection: &active_selection,
+                    input_map: &input_map,
+                    queries: &queries,
+                    renaming: &mut renaming,
+                    toggled: None,
+                    command: None,
+                    clicked: None,
+                    framed: false,
+                    dropped: None,
+                    action: None,
+                    renamed: None,
+                };
+                draw_rows(ui, &rows.rows, &mut tree);
+            });
+        });
+        started.elapsed()
+    }
+
+    /**
+     * Stress scene with every group expanded, so every entity gets a row
+     */
+    fn stress_app() -> (App, UpdateState, EntityHashSet) {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        stress_scene::spawn(&mut app.world, ENTITY_COUNT);
+
+        let mut state = UpdateState::new(&mut app.world);
+        let expanded = state
+            .get(&app.world)
+            .1
+            .roots
+            .iter()
+            .map(|(root, ..)| root)
+            .collect();
+
+        (app, state, expanded)
+    }
+
+    /**
+     * Rows are rebuilt once for the spawned scene, frames without changes reuse them
+     */
+    #[test]
+    fn unchanged_frame_keeps_rows() {
+        let (mut app, mut state, mut expanded) = stress_app();
+        let mut rows = HierarchyRows::default();
+
+        let (rebuilt, _) = timed_update(&mut state, &mut app.world, &mut rows, &mut expanded);
+        assert!(rebuilt);
+        assert_eq!(rows.rows.len(), ENTITY_COUNT);
+
+        app.update();
+
+        let (rebuilt, _) = timed_update(&mut state, &mut app.world, &mut rows, &mut expanded);
+        assert!(!rebuilt);
+        assert_eq!(rows.rows.len(), ENTITY_COUNT);
+    }
+
+    /**
+     * Panel stays within its budgets on the stress scene, from the tree to the laid out rows.
+     * Timing depends on the machine and the build, run with `cargo test --release -- --ignored`
+     */
+    #[test]
+    #[ignore]
+    fn stress_scene_within_budget() {
+        let (mut app, mut state, mut expanded) = stress_app();
+        let mut rows = HierarchyRows::default();
+        let ctx = Context::default();
+
+        // First egui frame loads fonts, it isn't part of the budget
+        timed_draw(&mut state, &mut app.world, &ctx, &rows);
+
+        let (rebuilt, rebuild_time) =
+            timed_update(&mut state, &mut app.world, &mut rows, &mut expanded);
+        assert!(rebuilt);
+        assert!(
+            rebuild_time < REBUILD_BUDGET,
+            "rebuild of {ENTITY_COUNT} entities took {rebuild_time:?}"
+        );
+
+        app.update();
+
+        let (rebuilt, update_time) =
+            timed_update(&mut state, &mut app.world, &mut rows, &mut expanded);
+        let frame_time = update_time + timed_draw(&mut state, &mut app.world, &ctx, &rows);
+        assert!(!rebuilt);
+        assert!(
+            frame_time < UNCHANGED_FRAME_BUDGET,
+            "frame without changes took {frame_time:?}"
+        );
+    }
+}
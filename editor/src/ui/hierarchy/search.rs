@@ -0,0 +1,202 @@
+use bevy::{
+    ecs::{
+        archetype::Archetypes,
+        component::Components,
+        entity::{Entities, Entity, EntityHashSet},
+        system::SystemParam,
+    },
+    utils::get_short_name,
+};
+
+use super::drag_and_drop::HierarchyQueries;
+
+const COMPONENT_PREFIX: &str = "t:";
+
+/// Text pattern, wildcards make it a glob matching the whole text
+enum Pattern {
+    Substring(String),
+    Glob(Vec<char>),
+}
+
+impl Pattern {
+    fn new(text: &str) -> Self {
+        let text = text.to_lowercase();
+
+        if text.contains(['*', '?']) {
+            Pattern::Glob(text.chars().collect())
+        } else {
+            Pattern::Substring(text)
+        }
+    }
+
+    fn matches(&self, text: &str, whole: bool) -> bool {
+        let text = text.to_lowercase();
+
+        match self {
+            Pattern::Substring(pattern) if whole => text == *pattern,
+            Pattern::Substring(pattern) => text.contains(pattern.as_str()),
+            Pattern::Glob(pattern) => glob_match(pattern, &text.chars().collect::<Vec<_>>()),
+        }
+    }
+}
+
+/**
+ * `*` matches any number of characters, `?` matches exactly one
+ */
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position of the last star and the text position it has been tried with
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // Star takes one more character
+                Some((star, star_t)) => {
+                    backtrack = Some((star, star_t + 1));
+                    p = star + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Contents of the search box: name pattern and components, that entity must have.
+/// `lamp t:PointLight` finds point lights with "lamp" in their name
+pub struct HierarchySearch {
+    name: Option<Pattern>,
+    components: Vec<Pattern>,
+}
+
+impl HierarchySearch {
+    /**
+     * None if there is nothing to search for
+     */
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut name = Vec::new();
+        let mut components = Vec::new();
+
+        for word in text.split_whitespace() {
+            match word.strip_prefix(COMPONENT_PREFIX) {
+                Some(component) if !component.is_empty() => {
+                    components.push(Pattern::new(component))
+                }
+                Some(_) => {}
+                None => name.push(word),
+            }
+        }
+
+        if name.is_empty() && components.is_empty() {
+            return None;
+        }
+
+        Some(HierarchySearch {
+            name: (!name.is_empty()).then(|| Pattern::new(&name.join(" "))),
+            components,
+        })
+    }
+
+    pub fn has_components(&self) -> bool {
+        !self.components.is_empty()
+    }
+
+    fn matches(&self, entity: Entity, name: &str, components: &EntityComponents) -> bool {
+        if self
+            .name
+            .as_ref()
+            .is_some_and(|pattern| !pattern.matches(name, false))
+        {
+            return false;
+        }
+
+        if self.components.is_empty() {
+            return true;
+        }
+
+        let names = components.short_names(entity);
+        self.components
+            .iter()
+            .all(|pattern| names.iter().any(|name| pattern.matches(name, true)))
+    }
+
+    /**
+     * Matching entities along with their ancestors, so they can be reached in the tree
+     */
+    pub fn visible_entities(
+        &self,
+        roots: Vec<Entity>,
+        queries: &HierarchyQueries,
+        components: &EntityComponents,
+    ) -> EntityHashSet {
+        let mut visible = EntityHashSet::default();
+
+        for root in roots {
+            self.visit(root, queries, components, &mut visible);
+        }
+
+        visible
+    }
+
+    fn visit(
+        &self,
+        entity: Entity,
+        queries: &HierarchyQueries,
+        components: &EntityComponents,
+        visible: &mut EntityHashSet,
+    ) -> bool {
+        let name = queries
+            .names
+            .get(entity)
+            .map_or(super::UNNAMED, |name| name.as_str());
+        let mut is_visible = self.matches(entity, name, components);
+
+        if let Ok(children) = queries.children.get(entity) {
+            for child in children {
+                is_visible |= self.visit(*child, queries, components, visible);
+            }
+        }
+
+        if is_visible {
+            visible.insert(entity);
+        }
+        is_visible
+    }
+}
+
+/// Component types of any entity, without querying for them
+#[derive(SystemParam)]
+pub struct EntityComponents<'w> {
+    entities: &'w Entities,
+    archetypes: &'w Archetypes,
+    components: &'w Components,
+}
+
+impl EntityComponents<'_> {
+    pub fn short_names(&self, entity: Entity) -> Vec<String> {
+        let Some(archetype) = self
+            .entities
+            .get(entity)
+            .and_then(|location| self.archetypes.get(location.archetype_id))
+        else {
+            return Vec::new();
+        };
+
+        archetype
+            .components()
+            .filter_map(|component_id| self.components.get_info(component_id))
+            .map(|info| get_short_name(info.name()))
+            .collect()
+    }
+}
@@ -0,0 +1,83 @@
+use std::any::TypeId;
+
+use bevy::{ecs::entity::Entity, render::view::Visibility};
+use bevy_egui::egui::{self, Color32, RichText, Ui};
+use common::editor_components::Locked;
+
+use crate::history::EditorCommand;
+
+use super::drag_and_drop::HierarchyQueries;
+
+/**
+ * Toggle drawn as a bare icon: strong when set on the entity, weak when it comes from an ancestor
+ */
+fn toggle_button(ui: &mut Ui, enabled: bool, icon: &str, color: Color32, hint: &str) -> bool {
+    let button = egui::Button::new(RichText::new(icon).color(color)).frame(false);
+
+    ui.add_enabled(enabled, button)
+        .on_hover_text(hint)
+        .clicked()
+}
+
+fn state_color(ui: &Ui, own: bool, inherited: bool) -> Color32 {
+    if own {
+        ui.visuals().strong_text_color()
+    } else if inherited {
+        ui.visuals().weak_text_color()
+    } else {
+        ui.visuals().weak_text_color().gamma_multiply(0.4)
+    }
+}
+
+/**
+ * Eye and lock of the row. Hiding and locking are passed down to children,
+ * so the toggles show whether the entity is affected by its ancestors
+ */
+pub fn draw(ui: &mut Ui, entity: Entity, queries: &HierarchyQueries) -> Option<EditorCommand> {
+    let mut command = None;
+
+    let visibility = queries.visibilities.get(entity).ok();
+    let (hidden, hidden_by_parent) =
+        visibility.map_or((false, false), |(visibility, inherited)| {
+            let hidden = *visibility == Visibility::Hidden;
+            (hidden, !hidden && !inherited.get())
+        });
+    let color = state_color(ui, !hidden && !hidden_by_parent, hidden_by_parent);
+
+    if toggle_button(ui, visibility.is_some(), "👁", color, "Show or hide") {
+        if let Some((visibility, _)) = visibility {
+            command = Some(EditorCommand::SetComponent {
+                entity,
+                type_id: TypeId::of::<Visibility>(),
+                name: "Visibility".to_owned(),
+                before: Box::new(*visibility),
+                after: Box::new(if hidden {
+                    Visibility::Inherited
+                } else {
+                    Visibility::Hidden
+                }),
+            });
+        }
+    }
+
+    let has_lock = queries.locked.has_lock(entity);
+    let is_locked = queries.locked.is_locked(entity);
+    let color = state_color(ui, has_lock, is_locked);
+    let icon = if is_locked { "🔒" } else { "🔓" };
+
+    if toggle_button(ui, true, icon, color, "Lock picking and moving in viewport") {
+        let components = vec![(
+            TypeId::of::<Locked>(),
+            "Locked".to_owned(),
+            Box::new(Locked) as _,
+        )];
+
+        command = Some(if has_lock {
+            EditorCommand::RemoveComponents { entity, components }
+        } else {
+            EditorCommand::AddComponents { entity, components }
+        });
+    }
+
+    command
+}
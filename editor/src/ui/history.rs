@@ -0,0 +1,68 @@
+use bevy::ecs::system::{ResMut, Resource};
+use bevy_egui::{
+    egui::{self, Label, RichText, ScrollArea, Sense},
+    EguiContexts,
+};
+
+use crate::history::History;
+
+#[derive(Resource, Default)]
+pub struct HistoryWindow {
+    pub open: bool,
+}
+
+pub fn draw(
+    mut contexts: EguiContexts,
+    mut history_window: ResMut<HistoryWindow>,
+    mut history: ResMut<History>,
+) {
+    if !history_window.open {
+        return;
+    }
+
+    let (applied, undone) = history.entries();
+
+    let mut open = true;
+    let mut jump_to = None;
+
+    egui::Window::new("History")
+        .open(&mut open)
+        .default_width(250.)
+        .show(contexts.ctx_mut(), |ui| {
+            ScrollArea::vertical().show(ui, |ui| {
+                if ui
+                    .selectable_label(applied.is_empty(), "Initial state")
+                    .clicked()
+                {
+                    jump_to = Some(0);
+                }
+
+                for (index, name) in applied.iter().enumerate() {
+                    if ui
+                        .selectable_label(index + 1 == applied.len(), name)
+                        .clicked()
+                    {
+                        jump_to = Some(index + 1);
+                    }
+                }
+
+                // Undone commands are shown dimmed until something new is done
+                for (index, name) in undone.iter().enumerate() {
+                    if ui
+                        .add(Label::new(RichText::new(name).weak()).sense(Sense::click()))
+                        .clicked()
+                    {
+                        jump_to = Some(applied.len() + index + 1);
+                    }
+                }
+            });
+        });
+
+    if !open {
+        history_window.open = false;
+    }
+
+    if let Some(applied) = jump_to {
+        history.jump_to(applied);
+    }
+}
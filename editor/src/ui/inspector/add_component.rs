@@ -0,0 +1,217 @@
+use std::any::TypeId;
+
+use bevy::{
+    ecs::{entity::Entity, reflect::ReflectComponent, world::World},
+    pbr::{
+        CascadeShadowConfig, Cascades, CascadesVisibleEntities, CubemapVisibleEntities,
+        DirectionalLight, PointLight, SpotLight,
+    },
+    reflect::{std_traits::ReflectDefault, Reflect},
+    render::{
+        primitives::{CascadesFrusta, CubemapFrusta, Frustum},
+        view::{InheritedVisibility, ViewVisibility, Visibility, VisibleEntities},
+    },
+    utils::get_short_name,
+};
+use bevy_egui::egui::{self, RichText, Ui};
+use common::{
+    editor_components::{EditorOnly, Locked, RootOrder},
+    scene_assets::{MaterialSource, MeshSource},
+};
+
+use crate::history::EditorCommand;
+
+use super::reflect_ui::ReflectUi;
+
+/**
+ * Components managed by the editor itself through the hierarchy, so they aren't offered here
+ */
+fn editor_internal_types() -> [TypeId; 3] {
+    [
+        TypeId::of::<EditorOnly>(),
+        TypeId::of::<Locked>(),
+        TypeId::of::<RootOrder>(),
+    ]
+}
+
+/// Components that only work together, like the ones of a bundle
+struct ComponentPreset {
+    name: &'static str,
+    components: fn() -> Vec<Box<dyn Reflect>>,
+}
+
+fn visibility() -> [Box<dyn Reflect>; 3] {
+    [
+        Box::new(Visibility::default()),
+        Box::new(InheritedVisibility::default()),
+        Box::new(ViewVisibility::default()),
+    ]
+}
+
+const PRESETS: &[ComponentPreset] = &[
+    ComponentPreset {
+        name: "Mesh",
+        components: || {
+            let mut components: Vec<Box<dyn Reflect>> = vec![
+                Box::new(MeshSource::default()),
+                Box::new(MaterialSource::default()),
+            ];
+            components.extend(visibility());
+            components
+        },
+    },
+    ComponentPreset {
+        name: "Point Light",
+        components: || {
+            let mut components: Vec<Box<dyn Reflect>> = vec![
+                Box::new(PointLight::default()),
+                Box::new(CubemapVisibleEntities::default()),
+                Box::new(CubemapFrusta::default()),
+            ];
+            components.extend(visibility());
+            components
+        },
+    },
+    ComponentPreset {
+        name: "Spot Light",
+        components: || {
+            let mut components: Vec<Box<dyn Reflect>> = vec![
+                Box::new(SpotLight::default()),
+                Box::new(VisibleEntities::default()),
+                Box::new(Frustum::default()),
+            ];
+            components.extend(visibility());
+            components
+        },
+    },
+    ComponentPreset {
+        name: "Directional Light",
+        components: || {
+            let mut components: Vec<Box<dyn Reflect>> = vec![
+                Box::new(DirectionalLight::default()),
+                Box::new(CascadesVisibleEntities::default()),
+                Box::new(CascadesFrusta::default()),
+                Box::new(Cascades::default()),
+                Box::new(CascadeShadowConfig::default()),
+            ];
+            components.extend(visibility());
+            components
+        },
+    },
+];
+
+/**
+ * Components of the preset missing on the entity. None if one of them can't be inserted through reflection
+ */
+fn missing_components(
+    world: &World,
+    reflect_ui: &ReflectUi,
+    entity: Entity,
+    preset: &ComponentPreset,
+) -> Option<Vec<Box<dyn Reflect>>> {
+    let entity_ref = world.get_entity(entity)?;
+    let mut missing = Vec::new();
+
+    for component in (preset.components)() {
+        let reflect_component = reflect_ui
+            .type_registry
+            .get_type_data::<ReflectComponent>(component.as_any().type_id())?;
+
+        if !reflect_component.contains(entity_ref) {
+            missing.push(component);
+        }
+    }
+
+    Some(missing)
+}
+
+fn add_components(entity: Entity, components: Vec<Box<dyn Reflect>>) -> EditorCommand {
+    EditorCommand::AddComponents {
+        entity,
+        components: components
+            .into_iter()
+            .map(|component| {
+                (
+                    component.as_any().type_id(),
+                    get_short_name(component.reflect_type_path()),
+                    component,
+                )
+            })
+            .collect(),
+    }
+}
+
+/**
+ * Searchable list of components with a default value, which entity doesn't have yet
+ */
+pub fn draw(
+    ui: &mut Ui,
+    world: &World,
+    reflect_ui: &ReflectUi,
+    entity: Entity,
+) -> Option<EditorCommand> {
+    let entity_ref = world.get_entity(entity)?;
+    let mut command = None;
+
+    ui.add_space(10.);
+
+    ui.menu_button(RichText::new("Add Component").size(16.), |ui| {
+        let search_id = ui.make_persistent_id("AddComponentSearch");
+        let mut search = ui.data_mut(|data| data.get_temp::<String>(search_id).unwrap_or_default());
+
+        let response = ui.add(egui::TextEdit::singleline(&mut search).hint_text("Search"));
+        if !response.has_focus() && search.is_empty() {
+            response.request_focus();
+        }
+        ui.data_mut(|data| data.insert_temp(search_id, search.clone()));
+
+        let search = search.to_lowercase();
+
+        egui::ScrollArea::vertical()
+            .max_height(300.)
+            .show(ui, |ui| {
+                for preset in PRESETS {
+                    if !preset.name.to_lowercase().contains(&search) {
+                        continue;
+                    }
+
+                    let missing = missing_components(world, reflect_ui, entity, preset)
+                        .filter(|missing| !missing.is_empty());
+                    let button = egui::Button::new(RichText::new(preset.name).strong());
+
+                    if ui.add_enabled(missing.is_some(), button).clicked() {
+                        command = missing.map(|missing| add_components(entity, missing));
+                        ui.close_menu();
+                    }
+                }
+
+                ui.separator();
+
+                let hidden = editor_internal_types();
+                let mut registrations = reflect_ui
+                    .type_registry
+                    .iter()
+                    .filter(|registration| !hidden.contains(&registration.type_id()))
+                    .filter_map(|registration| {
+                        let reflect_component = registration.data::<ReflectComponent>()?;
+                        let reflect_default = registration.data::<ReflectDefault>()?;
+                        let path = registration.type_info().type_path_table();
+
+                        (!reflect_component.contains(entity_ref)
+                            && path.path().to_lowercase().contains(&search))
+                        .then(|| (path.short_path(), path.path(), reflect_default))
+                    })
+                    .collect::<Vec<_>>();
+                registrations.sort_by_key(|(short_path, ..)| *short_path);
+
+                for (short_path, path, reflect_default) in registrations {
+                    if ui.button(short_path).on_hover_text(path).clicked() {
+                        command = Some(add_components(entity, vec![reflect_default.default()]));
+                        ui.close_menu();
+                    }
+                }
+            });
+    });
+
+    command
+}
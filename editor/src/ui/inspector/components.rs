@@ -0,0 +1,123 @@
+use std::any::TypeId;
+
+use bevy::{
+    ecs::{entity::Entity, reflect::ReflectComponent, world::World},
+    hierarchy::{Children, Parent},
+    render::view::{InheritedVisibility, ViewVisibility},
+    transform::components::{GlobalTransform, Transform},
+    utils::get_short_name,
+};
+use bevy_egui::egui::{collapsing_header::CollapsingState, Align, Layout, RichText, Ui};
+
+use crate::history::EditorCommand;
+
+use super::reflect_ui::{draw_read_only, ReflectUi};
+
+/**
+ * Components computed by engine systems, edits to them would be overwritten
+ */
+fn is_computed(type_id: TypeId) -> bool {
+    [
+        TypeId::of::<GlobalTransform>(),
+        TypeId::of::<InheritedVisibility>(),
+        TypeId::of::<ViewVisibility>(),
+        TypeId::of::<Parent>(),
+        TypeId::of::<Children>(),
+    ]
+    .contains(&type_id)
+}
+
+/**
+ * Draws every component of the entity, except transform, which has its own section.
+ * Returns commands for the components that were changed
+ */
+pub fn draw(
+    ui: &mut Ui,
+    world: &World,
+    reflect_ui: &ReflectUi,
+    entity: Entity,
+) -> Vec<EditorCommand> {
+    let mut commands = Vec::new();
+
+    let Some(entity_ref) = world.get_entity(entity) else {
+        return commands;
+    };
+
+    let mut components = entity_ref
+        .archetype()
+        .components()
+        .filter_map(|component_id| world.components().get_info(component_id))
+        .map(|info| (get_short_name(info.name()), info.type_id()))
+        .filter(|(_, type_id)| *type_id != Some(TypeId::of::<Transform>()))
+        .collect::<Vec<_>>();
+    components.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    for (name, type_id) in components {
+        ui.add_space(10.);
+
+        let reflected = type_id.and_then(|type_id| {
+            let reflect_component = reflect_ui
+                .type_registry
+                .get_type_data::<ReflectComponent>(type_id)?;
+            Some((type_id, reflect_component.reflect(entity_ref)?))
+        });
+
+        // Computed components belong to the ones they are computed from
+        let removable = reflected.is_some_and(|(type_id, _)| !is_computed(type_id));
+        let mut removed = false;
+
+        let id = ui.make_persistent_id(("Component", &name));
+        CollapsingState::load_with_default_open(ui.ctx(), id, true)
+            .show_header(ui, |ui| {
+                ui.label(RichText::new(&name).size(16.));
+
+                if removable {
+                    ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                        removed = ui
+                            .small_button("🗑")
+                            .on_hover_text("Remove component")
+                            .clicked();
+                    });
+                }
+            })
+            .body(|ui| {
+                let Some((type_id, component)) = reflected else {
+                    ui.label(RichText::new("Not registered for reflection").weak());
+                    return;
+                };
+
+                if is_computed(type_id) {
+                    draw_read_only(ui, component);
+                    return;
+                }
+
+                // Handles might not survive being copied through reflection
+                if let Some(path) = reflect_ui.handle(component) {
+                    ui.label(path);
+                    return;
+                }
+
+                // Editing a copy, so the change goes through history
+                let mut edited = reflect_ui.clone_value(component);
+
+                if reflect_ui.draw(ui, edited.as_mut()) {
+                    commands.push(EditorCommand::SetComponent {
+                        entity,
+                        type_id,
+                        name: name.clone(),
+                        before: reflect_ui.clone_value(component),
+                        after: edited,
+                    });
+                }
+            });
+
+        if let Some((type_id, component)) = reflected.filter(|_| removed) {
+            commands.push(EditorCommand::RemoveComponents {
+                entity,
+                components: vec![(type_id, name, reflect_ui.clone_value(component))],
+            });
+        }
+    }
+
+    commands
+}
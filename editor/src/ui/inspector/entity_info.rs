@@ -1,9 +1,7 @@
-use bevy::ecs::system::ResMut;
+use bevy::ecs::entity::Entity;
 use bevy_egui::egui::{CollapsingHeader, RichText, Ui};
 
-use crate::active_selection::ActiveSelection;
-
-pub fn draw(ui: &mut Ui, active_selection: &ResMut<ActiveSelection>) {
+pub fn draw(ui: &mut Ui, entity: Entity) {
     CollapsingHeader::new(RichText::new("Entity Info").size(16.))
         .default_open(true)
         .show(ui, |ui| {
@@ -11,7 +9,12 @@ pub fn draw(ui: &mut Ui, active_selection: &ResMut<ActiveSelection>) {
 
             ui.columns(2, |columns| {
                 columns[0].label(RichText::new("ID:").size(14.));
-                columns[1].label(RichText::new(active_selection.entity_id.to_string()).size(14.));
+                columns[1].label(RichText::new(entity.index().to_string()).size(14.));
+            });
+
+            ui.columns(2, |columns| {
+                columns[0].label(RichText::new("Generation:").size(14.));
+                columns[1].label(RichText::new(entity.generation().to_string()).size(14.));
             });
         });
 }
@@ -1,32 +1,149 @@
+mod add_component;
+mod components;
 mod entity_info;
+mod reflect_ui;
 mod transform_info;
 
-use bevy::ecs::system::ResMut;
+use bevy::{
+    asset::AssetServer,
+    ecs::{query::With, reflect::AppTypeRegistry, world::World},
+    hierarchy::Parent,
+    input::{keyboard::KeyCode, mouse::MouseButton, ButtonInput},
+    transform::components::{GlobalTransform, Transform},
+    window::PrimaryWindow,
+};
 use bevy_egui::{
     egui::{self, RichText, Sense},
-    EguiContexts,
+    EguiContext,
 };
 
-use crate::active_selection::ActiveSelection;
+use common::editor_components::EditorOnly;
+
+use crate::{
+    active_selection::ActiveSelection,
+    history::{EditorCommand, History},
+    input_map::{InputAction, InputMap},
+    settings::EditorSettings,
+    ui::editor_screen_space::OccupiedScreenSpace,
+};
+
+use self::reflect_ui::ReflectUi;
+
+/**
+ * Exclusive system, as components of the selected entity are only known at runtime
+ */
+pub fn draw(world: &mut World) {
+    let Ok(mut egui_context) = world
+        .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+        .get_single_mut(world)
+    else {
+        return;
+    };
+    let ctx = egui_context.get_mut().clone();
 
-pub fn draw(mut contexts: EguiContexts, active_selection: ResMut<ActiveSelection>) {
-    egui::SidePanel::right("Inspector")
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    let mut commands: Vec<(EditorCommand, &'static str)> = Vec::new();
+    let mut added = None;
+
+    let width = egui::SidePanel::right("Inspector")
         .resizable(true)
         .min_width(300.)
-        .show(contexts.ctx_mut(), |ui| {
+        .show(&ctx, |ui| {
             ui.layout().horizontal_justify();
             ui.add_space(10.);
             ui.heading(RichText::new("Inspector").size(30.));
             ui.add_space(5.);
 
-            if !active_selection.has_selection {
+            let active_selection = world.resource::<ActiveSelection>();
+
+            // Editor entities can be selected through the hierarchy while they are shown there
+            let show_editor_entities = world.resource::<EditorSettings>().show_editor_entities;
+            let Some(entity) = active_selection.active().filter(|entity| {
+                show_editor_entities || world.get::<EditorOnly>(*entity).is_none()
+            }) else {
                 ui.allocate_rect(ui.available_rect_before_wrap(), Sense::hover());
                 return;
+            };
+
+            if active_selection.entities().len() > 1 {
+                ui.label(
+                    RichText::new(format!(
+                        "{} entities selected, showing active one",
+                        active_selection.entities().len()
+                    ))
+                    .size(14.),
+                );
+                ui.add_space(5.);
             }
 
-            entity_info::draw(ui, &active_selection);
-            transform_info::draw(ui, &active_selection);
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                entity_info::draw(ui, entity);
+
+                // Reading the component every frame, so changes made by game systems are visible
+                if let Some(transform) = world.get::<Transform>(entity) {
+                    let mut edited = *transform;
+
+                    let settings = world.resource::<EditorSettings>();
+                    let invert_snap = world.resource::<InputMap>().held(
+                        InputAction::InvertSnap,
+                        world.resource::<ButtonInput<KeyCode>>(),
+                        world.resource::<ButtonInput<MouseButton>>(),
+                    );
+                    let snap = Some(&settings.snap).filter(|snap| snap.is_active(invert_snap));
+
+                    let parent_transform = world
+                        .get::<Parent>(entity)
+                        .and_then(|parent| world.get::<GlobalTransform>(parent.get()));
+
+                    // Writing back only on change, so dragging a value becomes a single undo step
+                    if transform_info::draw(
+                        ui,
+                        &mut edited,
+                        world.get::<GlobalTransform>(entity),
+                        parent_transform,
+                        settings.transform_space,
+                        snap,
+                    ) {
+                        commands.push((
+                            EditorCommand::SetTransform {
+                                changes: vec![(entity, *transform, edited)],
+                            },
+                            "inspector_transform",
+                        ));
+                    }
+                }
+
+                let reflect_ui = ReflectUi {
+                    type_registry: &type_registry,
+                    asset_server: world.get_resource::<AssetServer>(),
+                };
+
+                for command in components::draw(ui, world, &reflect_ui, entity) {
+                    commands.push((command, "inspector_component"));
+                }
+
+                added = add_component::draw(ui, world, &reflect_ui, entity);
+
+                ui.allocate_rect(ui.available_rect_before_wrap(), Sense::hover());
+            });
+        })
+        .response
+        .rect
+        .width();
+
+    let mut occupied_screen_space = world.resource_mut::<OccupiedScreenSpace>();
+    if occupied_screen_space.right != width {
+        occupied_screen_space.right = width;
+    }
+
+    let mut history = world.resource_mut::<History>();
+    for (command, merge_key) in commands {
+        history.push_merged(command, merge_key);
+    }
 
-            ui.allocate_rect(ui.available_rect_before_wrap(), Sense::hover());
-        });
+    if let Some(command) = added {
+        history.push(command);
+    }
 }
@@ -0,0 +1,420 @@
+use std::{any::TypeId, borrow::Cow};
+
+use bevy::{
+    asset::{AssetServer, ReflectHandle},
+    core::Name,
+    ecs::entity::Entity,
+    math::{EulerRot, Quat, Vec2, Vec3, Vec4},
+    reflect::{
+        std_traits::ReflectDefault, DynamicEnum, DynamicStruct, DynamicTuple, Enum, Reflect,
+        ReflectFromReflect, ReflectMut, ReflectRef, TypeInfo, TypeRegistry, VariantInfo,
+    },
+    render::color::Color,
+};
+use bevy_egui::egui::{self, CollapsingHeader, DragValue, RichText, Ui};
+
+/// Draws editable widgets for any reflected value
+pub struct ReflectUi<'a> {
+    pub type_registry: &'a TypeRegistry,
+    pub asset_server: Option<&'a AssetServer>,
+}
+
+impl ReflectUi<'_> {
+    /**
+     * Draws the value and returns true if it was changed
+     */
+    pub fn draw(&self, ui: &mut Ui, value: &mut dyn Reflect) -> bool {
+        if let Some(changed) = self.draw_known(ui, value) {
+            return changed;
+        }
+
+        match value.reflect_mut() {
+            ReflectMut::Struct(value) => {
+                let mut changed = false;
+                for index in 0..value.field_len() {
+                    let name = value.name_at(index).unwrap_or_default().to_owned();
+                    if let Some(field) = value.field_at_mut(index) {
+                        changed |= self.draw_field(ui, &name, field);
+                    }
+                }
+                changed
+            }
+            ReflectMut::TupleStruct(value) => {
+                let mut changed = false;
+                for index in 0..value.field_len() {
+                    if let Some(field) = value.field_mut(index) {
+                        changed |= self.draw_field(ui, &index.to_string(), field);
+                    }
+                }
+                changed
+            }
+            ReflectMut::Tuple(value) => {
+                let mut changed = false;
+                for index in 0..value.field_len() {
+                    if let Some(field) = value.field_mut(index) {
+                        changed |= self.draw_field(ui, &index.to_string(), field);
+                    }
+                }
+                changed
+            }
+            ReflectMut::List(value) => {
+                let mut changed = false;
+                let mut removed = None;
+
+                for index in 0..value.len() {
+                    ui.horizontal(|ui| {
+                        if ui.small_button("🗑").on_hover_text("Remove item").clicked() {
+                            removed = Some(index);
+                        }
+                        ui.vertical(|ui| {
+                            if let Some(item) = value.get_mut(index) {
+                                changed |= self.draw_field(ui, &index.to_string(), item);
+                            }
+                        });
+                    });
+                }
+
+                if let Some(index) = removed {
+                    value.remove(index);
+                    changed = true;
+                }
+
+                // New item is a copy of the last one, so item type doesn't need a default
+                let last = value
+                    .len()
+                    .checked_sub(1)
+                    .and_then(|index| value.get(index));
+                if let Some(last) = last.map(|last| self.clone_value(last)) {
+                    if ui.small_button("Add item").clicked() {
+                        value.push(last);
+                        changed = true;
+                    }
+                } else {
+                    ui.label(RichText::new("Empty").weak());
+                }
+
+                changed
+            }
+            ReflectMut::Array(value) => {
+                let mut changed = false;
+                for index in 0..value.len() {
+                    if let Some(item) = value.get_mut(index) {
+                        changed |= self.draw_field(ui, &index.to_string(), item);
+                    }
+                }
+                changed
+            }
+            ReflectMut::Map(value) => {
+                let mut changed = false;
+                for index in 0..value.len() {
+                    if let Some((key, item)) = value.get_at_mut(index) {
+                        changed |= self.draw_field(ui, &format!("{key:?}"), item);
+                    }
+                }
+                changed
+            }
+            ReflectMut::Enum(value) => self.draw_enum(ui, value),
+            ReflectMut::Value(value) => {
+                draw_read_only(ui, value);
+                false
+            }
+        }
+    }
+
+    /**
+     * Draws the field on the same line as its name, or in a collapsable section if it has fields of its own
+     */
+    fn draw_field(&self, ui: &mut Ui, name: &str, value: &mut dyn Reflect) -> bool {
+        if self.is_inline(value) {
+            return ui
+                .horizontal(|ui| {
+                    ui.label(RichText::new(name).size(14.));
+                    self.draw(ui, value)
+                })
+                .inner;
+        }
+
+        CollapsingHeader::new(RichText::new(name).size(14.))
+            .default_open(true)
+            .show(ui, |ui| self.draw(ui, value))
+            .body_returned
+            .unwrap_or_default()
+    }
+
+    fn is_inline(&self, value: &dyn Reflect) -> bool {
+        if self.is_known(value) {
+            return true;
+        }
+
+        match value.reflect_ref() {
+            ReflectRef::Value(_) => true,
+            ReflectRef::Enum(value) => value.field_len() == 0,
+            _ => false,
+        }
+    }
+
+    fn is_known(&self, value: &dyn Reflect) -> bool {
+        let any = value.as_any();
+
+        any.is::<f32>()
+            || any.is::<f64>()
+            || any.is::<i8>()
+            || any.is::<i16>()
+            || any.is::<i32>()
+            || any.is::<i64>()
+            || any.is::<isize>()
+            || any.is::<u8>()
+            || any.is::<u16>()
+            || any.is::<u32>()
+            || any.is::<u64>()
+            || any.is::<usize>()
+            || any.is::<bool>()
+            || any.is::<String>()
+            || any.is::<Cow<'static, str>>()
+            || any.is::<Name>()
+            || any.is::<Entity>()
+            || any.is::<Vec2>()
+            || any.is::<Vec3>()
+            || any.is::<Vec4>()
+            || any.is::<Quat>()
+            || any.is::<Color>()
+            || self.handle(value).is_some()
+    }
+
+    /**
+     * Widgets for types that are better edited as a whole, than field by field.
+     * Returns None if value isn't one of them
+     */
+    fn draw_known(&self, ui: &mut Ui, value: &mut dyn Reflect) -> Option<bool> {
+        if let Some(path) = self.handle(value) {
+            ui.label(path);
+            return Some(false);
+        }
+
+        let any = value.as_any_mut();
+
+        macro_rules! drag_value {
+            ($($number:ty: $speed:expr),*) => {
+                $(
+                    if let Some(number) = any.downcast_mut::<$number>() {
+                        return Some(
+                            ui.add(DragValue::new(number).speed($speed).max_decimals(3))
+                                .changed(),
+                        );
+                    }
+                )*
+            };
+        }
+
+        drag_value!(
+            f32: 0.05, f64: 0.05,
+            i8: 1., i16: 1., i32: 1., i64: 1., isize: 1.,
+            u8: 1., u16: 1., u32: 1., u64: 1., usize: 1.
+        );
+
+        if let Some(value) = any.downcast_mut::<bool>() {
+            return Some(ui.checkbox(value, "").changed());
+        }
+
+        if let Some(value) = any.downcast_mut::<String>() {
+            return Some(ui.text_edit_singleline(value).changed());
+        }
+
+        if let Some(value) = any.downcast_mut::<Cow<'static, str>>() {
+            let mut text = value.to_string();
+            let changed = ui.text_edit_singleline(&mut text).changed();
+            if changed {
+                *value = Cow::Owned(text);
+            }
+            return Some(changed);
+        }
+
+        // Name keeps a hash of the string, which has to be updated with it
+        if let Some(value) = any.downcast_mut::<Name>() {
+            let mut text = value.as_str().to_owned();
+            let changed = ui.text_edit_singleline(&mut text).changed();
+            if changed {
+                value.set(text);
+            }
+            return Some(changed);
+        }
+
+        if let Some(value) = any.downcast_mut::<Entity>() {
+            ui.label(format!("{value:?}"));
+            return Some(false);
+        }
+
+        if let Some(value) = any.downcast_mut::<Vec2>() {
+            return Some(draw_axes(ui, &mut value.to_array()[..], |axes| {
+                *value = Vec2::from_slice(axes)
+            }));
+        }
+
+        if let Some(value) = any.downcast_mut::<Vec3>() {
+            return Some(draw_axes(ui, &mut value.to_array()[..], |axes| {
+                *value = Vec3::from_slice(axes)
+            }));
+        }
+
+        if let Some(value) = any.downcast_mut::<Vec4>() {
+            return Some(draw_axes(ui, &mut value.to_array()[..], |axes| {
+                *value = Vec4::from_slice(axes)
+            }));
+        }
+
+        // Rotation is edited in degrees, same as in transform info
+        if let Some(value) = any.downcast_mut::<Quat>() {
+            let (y, x, z) = value.to_euler(EulerRot::YXZ);
+            let mut degrees = [x.to_degrees(), y.to_degrees(), z.to_degrees()];
+            return Some(draw_axes(ui, &mut degrees, |degrees| {
+                *value = Quat::from_euler(
+                    EulerRot::YXZ,
+                    degrees[1].to_radians(),
+                    degrees[0].to_radians(),
+                    degrees[2].to_radians(),
+                )
+            }));
+        }
+
+        if let Some(value) = any.downcast_mut::<Color>() {
+            let mut rgba = value.as_rgba_f32();
+            let changed = ui.color_edit_button_rgba_unmultiplied(&mut rgba).changed();
+            if changed {
+                *value = Color::rgba(rgba[0], rgba[1], rgba[2], rgba[3]);
+            }
+            return Some(changed);
+        }
+
+        None
+    }
+
+    /**
+     * Handles are shown by the path of the asset, they can't be created from it without knowing the asset type
+     */
+    pub fn handle(&self, value: &dyn Reflect) -> Option<String> {
+        let reflect_handle = self
+            .type_registry
+            .get_type_data::<ReflectHandle>(value.as_any().type_id())?;
+        let handle = reflect_handle.downcast_handle_untyped(value.as_any())?;
+
+        let path = self
+            .asset_server
+            .and_then(|asset_server| asset_server.get_path(handle.id()));
+
+        Some(match path {
+            Some(path) => path.to_string(),
+            None => format!("{:?}", handle.id()),
+        })
+    }
+
+    fn draw_enum(&self, ui: &mut Ui, value: &mut dyn Enum) -> bool {
+        let mut changed = false;
+
+        if let Some(TypeInfo::Enum(info)) = value.get_represented_type_info() {
+            let current = value.variant_name().to_owned();
+            let mut selected = None;
+
+            egui::ComboBox::from_id_source(ui.id().with("variant"))
+                .selected_text(&current)
+                .show_ui(ui, |ui| {
+                    for variant in info.iter() {
+                        // Variants with fields without a default can't be switched to
+                        let default = self.variant_default(variant);
+                        let response = ui.add_enabled(
+                            default.is_some(),
+                            egui::SelectableLabel::new(variant.name() == current, variant.name()),
+                        );
+                        if response.clicked() && variant.name() != current {
+                            selected = default;
+                        }
+                    }
+                });
+
+            if let Some(default) = selected {
+                value.apply(&default);
+                changed = true;
+            }
+        } else {
+            ui.label(value.variant_name());
+        }
+
+        for index in 0..value.field_len() {
+            let name = value
+                .name_at(index)
+                .map_or_else(|| index.to_string(), str::to_owned);
+            if let Some(field) = value.field_at_mut(index) {
+                changed |= self.draw_field(ui, &name, field);
+            }
+        }
+
+        changed
+    }
+
+    fn variant_default(&self, variant: &VariantInfo) -> Option<DynamicEnum> {
+        Some(match variant {
+            VariantInfo::Unit(variant) => DynamicEnum::new(variant.name(), ()),
+            VariantInfo::Tuple(variant) => {
+                let mut fields = DynamicTuple::default();
+                for field in variant.iter() {
+                    fields.insert_boxed(self.default_value(field.type_id())?);
+                }
+                DynamicEnum::new(variant.name(), fields)
+            }
+            VariantInfo::Struct(variant) => {
+                let mut fields = DynamicStruct::default();
+                for field in variant.iter() {
+                    fields.insert_boxed(field.name(), self.default_value(field.type_id())?);
+                }
+                DynamicEnum::new(variant.name(), fields)
+            }
+        })
+    }
+
+    fn default_value(&self, type_id: TypeId) -> Option<Box<dyn Reflect>> {
+        self.type_registry
+            .get_type_data::<ReflectDefault>(type_id)
+            .map(ReflectDefault::default)
+    }
+
+    /**
+     * Copies the value as its concrete type when possible, so widgets of known types can be used for it
+     */
+    pub fn clone_value(&self, value: &dyn Reflect) -> Box<dyn Reflect> {
+        value
+            .get_represented_type_info()
+            .and_then(|info| {
+                self.type_registry
+                    .get_type_data::<ReflectFromReflect>(info.type_id())
+            })
+            .and_then(|from_reflect| from_reflect.from_reflect(value))
+            .unwrap_or_else(|| value.clone_value())
+    }
+}
+
+/**
+ * Draws a drag value per axis in a single row, calling `set` with all axes when one of them changed
+ */
+fn draw_axes(ui: &mut Ui, axes: &mut [f32], set: impl FnOnce(&[f32])) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        for axis in axes.iter_mut() {
+            changed |= ui
+                .add(DragValue::new(axis).speed(0.05).max_decimals(3))
+                .changed();
+        }
+    });
+
+    if changed {
+        set(axes);
+    }
+
+    changed
+}
+
+/**
+ * Fallback for values that can only be reflected as a whole
+ */
+pub fn draw_read_only(ui: &mut Ui, value: &dyn Reflect) {
+    ui.label(RichText::new(format!("{value:?}")).weak());
+}
@@ -1,76 +1,140 @@
-use bevy::{ecs::system::ResMut, math::EulerRot};
-use bevy_egui::egui::{CollapsingHeader, RichText, Ui, WidgetText};
+use bevy::{
+    math::{EulerRot, Quat, Vec3},
+    transform::components::{GlobalTransform, Transform},
+};
+use bevy_egui::egui::{CollapsingHeader, DragValue, RichText, Ui, WidgetText};
 
-use crate::active_selection::ActiveSelection;
+use crate::settings::{SnapSettings, TransformSpace};
+
+/**
+ * Draws both local and world transform, only the one in the chosen space is editable.
+ * Return true if local transform was changed
+ */
+pub fn draw(
+    ui: &mut Ui,
+    transform: &mut Transform,
+    global_transform: Option<&GlobalTransform>,
+    parent_transform: Option<&GlobalTransform>,
+    space: TransformSpace,
+    snap: Option<&SnapSettings>,
+) -> bool {
+    let mut changed = false;
+
+    changed |= ui
+        .add_enabled_ui(space == TransformSpace::Local, |ui| {
+            draw_section(ui, "Local Transform Info", transform, snap)
+        })
+        .inner;
+
+    let mut world = global_transform.map_or(*transform, GlobalTransform::compute_transform);
+
+    let world_changed = ui
+        .add_enabled_ui(space == TransformSpace::World, |ui| {
+            draw_section(ui, "World Transform Info", &mut world, snap)
+        })
+        .inner;
+
+    // Converting world values back through the parent chain, as only local transform is stored
+    if world_changed {
+        *transform = match parent_transform {
+            Some(parent_transform) => GlobalTransform::from(world).reparented_to(parent_transform),
+            None => world,
+        };
+        changed = true;
+    }
+
+    changed
+}
+
+fn draw_section(
+    ui: &mut Ui,
+    title: &str,
+    transform: &mut Transform,
+    snap: Option<&SnapSettings>,
+) -> bool {
+    let mut changed = false;
 
-pub fn draw(ui: &mut Ui, active_selection: &ResMut<ActiveSelection>) {
     ui.add_space(10.);
 
-    CollapsingHeader::new(RichText::new("Local Transform Info").size(16.))
+    CollapsingHeader::new(RichText::new(title).size(16.))
         .default_open(true)
         .show(ui, |ui| {
             ui.add_space(10.);
 
             // Position
-            let position = active_selection.transform.translation;
-            draw_collapasable_with_xyz_columns(
+            changed |= draw_collapasable_with_xyz_columns(
                 ui,
                 RichText::new("Position Info").size(16.),
-                position.x,
-                position.y,
-                position.z,
+                &mut transform.translation,
+                0.05,
+                snap.map(|snap| |value| snap.translation(value)),
             );
 
-            // Rotation
-            let rotation = active_selection
-                .transform
-                .rotation
-                .to_euler(EulerRot::default());
-            draw_collapasable_with_xyz_columns(
+            // Rotation is edited in degrees, but stored as quaternion
+            let (y, x, z) = transform.rotation.to_euler(EulerRot::YXZ);
+            let mut rotation = Vec3::new(x.to_degrees(), y.to_degrees(), z.to_degrees());
+            if draw_collapasable_with_xyz_columns(
                 ui,
                 RichText::new("Rotation Info").size(16.),
-                rotation.0,
-                rotation.1,
-                rotation.2,
-            );
+                &mut rotation,
+                0.5,
+                snap.map(|snap| |value| snap.rotation_degrees(value)),
+            ) {
+                transform.rotation = Quat::from_euler(
+                    EulerRot::YXZ,
+                    rotation.y.to_radians(),
+                    rotation.x.to_radians(),
+                    rotation.z.to_radians(),
+                );
+                changed = true;
+            }
 
             //Scale
-            let scale = active_selection.transform.scale;
-            draw_collapasable_with_xyz_columns(
+            changed |= draw_collapasable_with_xyz_columns(
                 ui,
                 RichText::new("Scale Info").size(16.),
-                scale.x,
-                scale.y,
-                scale.z,
+                &mut transform.scale,
+                0.01,
+                snap.map(|snap| |value| snap.scale(value)),
             );
         });
+
+    changed
 }
 
 fn draw_collapasable_with_xyz_columns(
     ui: &mut Ui,
     header: impl Into<WidgetText>,
-    x: f32,
-    y: f32,
-    z: f32,
-) {
+    value: &mut Vec3,
+    speed: f64,
+    snap: Option<impl Fn(f32) -> f32>,
+) -> bool {
+    let mut changed = false;
+
     ui.add_space(10.);
 
     CollapsingHeader::new(header)
         .default_open(true)
         .show(ui, |ui| {
-            ui.columns(2, |columns| {
-                columns[0].label(RichText::new("X:").size(14.));
-                columns[1].label(RichText::new(x.to_string()).size(14.));
-            });
-
-            ui.columns(2, |columns| {
-                columns[0].label(RichText::new("Y:").size(14.));
-                columns[1].label(RichText::new(y.to_string()).size(14.));
-            });
-
-            ui.columns(2, |columns| {
-                columns[0].label(RichText::new("Z:").size(14.));
-                columns[1].label(RichText::new(z.to_string()).size(14.));
-            });
+            for (label, axis) in [
+                ("X:", &mut value.x),
+                ("Y:", &mut value.y),
+                ("Z:", &mut value.z),
+            ] {
+                ui.columns(2, |columns| {
+                    columns[0].label(RichText::new(label).size(14.));
+                    if columns[1]
+                        .add(DragValue::new(axis).speed(speed).max_decimals(3))
+                        .changed()
+                    {
+                        if let Some(snap) = &snap {
+                            *axis = snap(*axis);
+                        }
+                        changed = true;
+                    }
+                });
+            }
         });
+
+    changed
 }
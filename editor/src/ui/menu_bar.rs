@@ -0,0 +1,277 @@
+use std::path::PathBuf;
+
+use bevy::{
+    ecs::{
+        event::EventWriter,
+        query::With,
+        system::{Query, Res, ResMut, Resource, SystemParam},
+    },
+    render::camera::Projection,
+};
+use bevy_egui::{
+    egui::{self, Key, RichText},
+    EguiContexts,
+};
+
+use crate::{
+    camera::{AxisView, CameraBookmark, ChangeView, FrameSelection, PanOrbitCamera},
+    input_map::{EditorInput, InputAction, BOOKMARK_KEYS},
+    scene_file::{
+        with_scene_extension, LoadScene, SaveScene, SceneEditorState, SceneFile, SCENE_EXTENSION,
+    },
+    settings::EditorSettings,
+    ui::bindings::BindingsWindow,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+enum FileDialogMode {
+    Open,
+    SaveAs,
+}
+
+/// Path prompt shown for "Open" and "Save As"
+#[derive(Resource, Default)]
+pub struct FileDialog {
+    mode: Option<FileDialogMode>,
+    path: String,
+}
+
+impl FileDialog {
+    fn open(&mut self, mode: FileDialogMode, current: Option<&PathBuf>) {
+        self.mode = Some(mode);
+        self.path = current.map_or_else(
+            || format!("scene.{SCENE_EXTENSION}"),
+            |path| path.display().to_string(),
+        );
+    }
+}
+
+/// Menu entries picked this frame, either by click or by shortcut
+#[derive(Default)]
+struct MenuActions {
+    new: bool,
+    new_sample: bool,
+    open: bool,
+    save: bool,
+    save_as: bool,
+}
+
+/// Events sent from the menu to the scene and the camera
+#[derive(SystemParam)]
+pub struct MenuEvents<'w> {
+    save_scene: EventWriter<'w, SaveScene>,
+    load_scene: EventWriter<'w, LoadScene>,
+    change_view: EventWriter<'w, ChangeView>,
+    frame_selection: EventWriter<'w, FrameSelection>,
+    camera_bookmark: EventWriter<'w, CameraBookmark>,
+}
+
+fn menu_button(ui: &mut egui::Ui, text: &str, shortcut: Option<String>) -> bool {
+    let mut button = egui::Button::new(text);
+    if let Some(shortcut) = shortcut {
+        button = button.shortcut_text(shortcut);
+    }
+
+    let clicked = ui.add(button).clicked();
+    if clicked {
+        ui.close_menu();
+    }
+    clicked
+}
+
+pub fn draw(
+    mut contexts: EguiContexts,
+    scene_file: Res<SceneFile>,
+    mut settings: ResMut<EditorSettings>,
+    mut file_dialog: ResMut<FileDialog>,
+    events: MenuEvents,
+    editor_state: Res<SceneEditorState>,
+    projections: Query<&Projection, With<PanOrbitCamera>>,
+    input: EditorInput,
+    mut bindings_window: ResMut<BindingsWindow>,
+) {
+    let MenuEvents {
+        mut save_scene,
+        mut load_scene,
+        mut change_view,
+        mut frame_selection,
+        mut camera_bookmark,
+    } = events;
+    let ctx = contexts.ctx_mut();
+
+    let input_map = &input.map;
+    // Text fields, including the file dialog, take the keys for themselves
+    let mut actions = if ctx.wants_keyboard_input() {
+        MenuActions::default()
+    } else {
+        MenuActions {
+            new: input.just_pressed(InputAction::NewScene),
+            new_sample: false,
+            open: input.just_pressed(InputAction::OpenScene),
+            save: input.just_pressed(InputAction::SaveScene),
+            save_as: input.just_pressed(InputAction::SaveSceneAs),
+        }
+    };
+    let shortcut = |action| Some(input_map.shortcut_text(action));
+
+    egui::TopBottomPanel::top("MenuBar").show(ctx, |ui| {
+        egui::menu::bar(ui, |ui| {
+            ui.menu_button("File", |ui| {
+                actions.new |= menu_button(ui, "New", shortcut(InputAction::NewScene));
+                actions.new_sample |= menu_button(ui, "New Sample Scene", None);
+                actions.open |= menu_button(ui, "Open...", shortcut(InputAction::OpenScene));
+
+                ui.separator();
+
+                actions.save |= menu_button(ui, "Save", shortcut(InputAction::SaveScene));
+                actions.save_as |=
+                    menu_button(ui, "Save As...", shortcut(InputAction::SaveSceneAs));
+            });
+
+            ui.menu_button("View", |ui| {
+                let orthographic = projections
+                    .iter()
+                    .any(|projection| matches!(projection, Projection::Orthographic(_)));
+                let toggle = egui::Button::new("Orthographic")
+                    .selected(orthographic)
+                    .shortcut_text(input_map.shortcut_text(InputAction::ToggleProjection));
+                if ui.add(toggle).clicked() {
+                    change_view.send(ChangeView::TogglePerspective);
+                    ui.close_menu();
+                }
+
+                ui.separator();
+
+                for view in AxisView::ALL {
+                    let button = egui::Button::new(view.name())
+                        .shortcut_text(input_map.shortcut_text(view.action()));
+                    if ui.add(button).clicked() {
+                        change_view.send(ChangeView::Axis(view));
+                        ui.close_menu();
+                    }
+                }
+
+                ui.separator();
+
+                let button = egui::Button::new("Frame Selected")
+                    .shortcut_text(input_map.shortcut_text(InputAction::FrameSelection));
+                if ui.add(button).clicked() {
+                    frame_selection.send(FrameSelection);
+                    ui.close_menu();
+                }
+
+                ui.menu_button("Bookmarks", |ui| {
+                    ui.menu_button("Save Current View", |ui| {
+                        for number in 1..=BOOKMARK_KEYS.len() as u8 {
+                            let button = egui::Button::new(format!("Bookmark {number}"))
+                                .shortcut_text(
+                                    input_map.shortcut_text(InputAction::SaveBookmark(number)),
+                                );
+                            if ui.add(button).clicked() {
+                                camera_bookmark.send(CameraBookmark::Save(number));
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                    ui.separator();
+
+                    if editor_state.bookmarks.is_empty() {
+                        ui.label(RichText::new("No saved bookmarks").weak());
+                    }
+
+                    for number in editor_state.bookmarks.keys() {
+                        let button = egui::Button::new(format!("Bookmark {number}")).shortcut_text(
+                            input_map.shortcut_text(InputAction::RecallBookmark(*number)),
+                        );
+                        if ui.add(button).clicked() {
+                            camera_bookmark.send(CameraBookmark::Recall(*number));
+                            ui.close_menu();
+                        }
+                    }
+                });
+            });
+
+            ui.menu_button("Settings", |ui| {
+                if ui.button("Input Bindings...").clicked() {
+                    bindings_window.open = true;
+                    ui.close_menu();
+                }
+            });
+
+            ui.menu_button("Debug", |ui| {
+                ui.checkbox(&mut settings.show_editor_entities, "Show Editor Entities");
+            });
+        });
+    });
+
+    if actions.new {
+        load_scene.send(LoadScene::Empty);
+    } else if actions.new_sample {
+        load_scene.send(LoadScene::Sample);
+    } else if actions.open {
+        file_dialog.open(FileDialogMode::Open, scene_file.path.as_ref());
+    }
+
+    // Scene without a file yet has nowhere to be saved to
+    match (&scene_file.path, actions.save, actions.save_as) {
+        (Some(path), true, false) => {
+            save_scene.send(SaveScene { path: path.clone() });
+        }
+        (_, true, _) | (_, _, true) => {
+            file_dialog.open(FileDialogMode::SaveAs, scene_file.path.as_ref());
+        }
+        _ => {}
+    }
+
+    let Some(mode) = file_dialog.mode else {
+        return;
+    };
+
+    let (title, confirm) = match mode {
+        FileDialogMode::Open => ("Open Scene", "Open"),
+        FileDialogMode::SaveAs => ("Save Scene As", "Save"),
+    };
+
+    let mut open = true;
+    let mut confirmed = false;
+
+    egui::Window::new(title)
+        .id(egui::Id::new("FileDialog"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Path:");
+                let response = ui.text_edit_singleline(&mut file_dialog.path);
+                confirmed |=
+                    response.lost_focus() && ui.input(|input| input.key_pressed(Key::Enter));
+            });
+
+            if mode == FileDialogMode::Open {
+                ui.label("Unsaved changes of the current scene will be lost");
+            }
+
+            confirmed |= ui.button(confirm).clicked();
+        });
+
+    let path = file_dialog.path.trim();
+
+    if confirmed && !path.is_empty() {
+        match mode {
+            FileDialogMode::Open => {
+                load_scene.send(LoadScene::File(PathBuf::from(path)));
+            }
+            FileDialogMode::SaveAs => {
+                save_scene.send(SaveScene {
+                    path: with_scene_extension(path),
+                });
+            }
+        }
+        open = false;
+    }
+
+    if !open {
+        file_dialog.mode = None;
+    }
+}
@@ -1,117 +1,56 @@
+pub mod bindings;
 pub mod editor_screen_space;
 pub mod hierarchy;
+pub mod history;
 pub mod inspector;
+pub mod menu_bar;
+pub mod toolbar;
 
 use bevy::{
-    app::{Plugin, Startup, Update},
-    asset::Assets,
-    core::Name,
-    ecs::system::{Commands, ResMut, Resource},
-    hierarchy::BuildChildren,
-    math::primitives::{Cuboid, Plane3d},
-    pbr::{AmbientLight, PbrBundle, PointLight, PointLightBundle, StandardMaterial},
-    render::{
-        color::Color,
-        mesh::{Mesh, Meshable},
-    },
-    transform::components::Transform,
+    app::{Plugin, PostUpdate, Update},
+    diagnostic::{Diagnostic, RegisterDiagnostic},
+    ecs::schedule::IntoSystemConfigs,
 };
-use bevy_mod_picking::PickableBundle;
 
-use self::editor_screen_space::OccupiedScreenSpace;
+use crate::scene_file::{load_scene, save_scene};
 
-fn init(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-) {
-    commands.insert_resource(AmbientLight {
-        color: Color::WHITE,
-        brightness: 50.,
-    });
-
-    commands
-        .spawn(PbrBundle {
-            mesh: meshes.add(Plane3d::default().mesh().size(5., 5.)),
-            material: materials.add(Color::rgb(0.3, 0.5, 0.3)),
-            ..Default::default()
-        })
-        .insert(Name::new("Plane"))
-        .insert(PickableBundle::default());
-
-    commands
-        .spawn(PbrBundle {
-            mesh: meshes.add(Cuboid::default().mesh()),
-            material: materials.add(Color::rgb(0.8, 0.7, 0.6)),
-            transform: Transform::from_xyz(0.0, 0.5, 0.0),
-            ..Default::default()
-        })
-        .insert(Name::new("Cuboid"))
-        .insert(PickableBundle::default());
-
-    commands
-        .spawn(PbrBundle {
-            mesh: meshes.add(Cuboid::default().mesh()),
-            material: materials.add(Color::rgb(0.8, 0.7, 0.6)),
-            transform: Transform::from_xyz(0.0, -1.0, 0.0),
-            ..Default::default()
-        })
-        .insert(Name::new("Cuboid"))
-        .insert(PickableBundle::default());
-
-    commands
-        .spawn(PbrBundle {
-            mesh: meshes.add(Cuboid::default().mesh()),
-            material: materials.add(Color::rgb(0.8, 0.7, 0.6)),
-            transform: Transform::from_xyz(1.0, 3.0, 1.0),
-            ..Default::default()
-        })
-        .insert(Name::new("Cuboid"))
-        .insert(PickableBundle::default())
-        .with_children(|parent| {
-            parent
-                .spawn(PbrBundle {
-                    mesh: meshes.add(Cuboid::default().mesh()),
-                    material: materials.add(Color::rgb(0.8, 0.7, 0.6)),
-                    transform: Transform::from_xyz(2.0, 4.0, 2.0),
-                    ..Default::default()
-                })
-                .insert(Name::new("Cuboid"))
-                .insert(PickableBundle::default())
-                .with_children(|parent| {
-                    parent
-                        .spawn(PbrBundle {
-                            mesh: meshes.add(Cuboid::default().mesh()),
-                            material: materials.add(Color::rgb(0.8, 0.7, 0.6)),
-                            transform: Transform::from_xyz(2.0, -2.0, 2.0),
-                            ..Default::default()
-                        })
-                        .insert(Name::new("Cuboid"))
-                        .insert(PickableBundle::default());
-                });
-        });
-
-    commands
-        .spawn(PointLightBundle {
-            point_light: PointLight {
-                intensity: 20500.0,
-                shadows_enabled: true,
-                ..Default::default()
-            },
-            transform: Transform::from_xyz(1.0, 1.0, 1.0),
-            ..Default::default()
-        })
-        .insert(Name::new("Point Light"))
-        .insert(PickableBundle::default());
-}
+use self::{
+    bindings::BindingsWindow, editor_screen_space::OccupiedScreenSpace, hierarchy::HierarchyPanel,
+    history::HistoryWindow, menu_bar::FileDialog,
+};
 
 pub struct EditorUiPlugin;
 
 impl Plugin for EditorUiPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.init_resource::<OccupiedScreenSpace>()
-            .add_systems(Startup, init)
-            .add_systems(Update, hierarchy::draw)
-            .add_systems(Update, inspector::draw);
+            .init_resource::<HierarchyPanel>()
+            .init_resource::<HistoryWindow>()
+            .init_resource::<BindingsWindow>()
+            .init_resource::<FileDialog>()
+            .register_diagnostic(Diagnostic::new(hierarchy::DRAW_TIME).with_suffix("ms"))
+            .register_diagnostic(Diagnostic::new(hierarchy::REBUILD_TIME).with_suffix("ms"))
+            // Menu bar and toolbar go first, so they take the whole width of the window
+            .add_systems(
+                Update,
+                (
+                    menu_bar::draw,
+                    toolbar::draw,
+                    (
+                        hierarchy::draw,
+                        inspector::draw,
+                        history::draw,
+                        bindings::draw,
+                    ),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    hierarchy::store_expanded.before(save_scene),
+                    hierarchy::restore_expanded.after(load_scene),
+                ),
+            );
     }
 }
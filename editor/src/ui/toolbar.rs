@@ -0,0 +1,125 @@
+use bevy::ecs::system::{Res, ResMut};
+use bevy_egui::{
+    egui::{self, DragValue, RichText},
+    EguiContexts,
+};
+
+use super::{editor_screen_space::OccupiedScreenSpace, history::HistoryWindow};
+use crate::{
+    history::History,
+    input_map::{InputAction, InputMap},
+    manipulator::ManipulatorMode,
+    settings::{EditorSettings, TransformSpace},
+};
+
+pub fn draw(
+    mut contexts: EguiContexts,
+    mut mode: ResMut<ManipulatorMode>,
+    mut settings: ResMut<EditorSettings>,
+    mut history: ResMut<History>,
+    mut history_window: ResMut<HistoryWindow>,
+    input_map: Res<InputMap>,
+    mut occupied_screen_space: ResMut<OccupiedScreenSpace>,
+) {
+    // Editing copies, so resources are marked as changed only when something was actually changed
+    let mut edited_mode = *mode;
+    let mut snap = settings.snap.clone();
+    let mut space = settings.transform_space;
+    let mut history_open = history_window.open;
+
+    let response = egui::TopBottomPanel::top("Toolbar").show(contexts.ctx_mut(), |ui| {
+        ui.horizontal(|ui| {
+            if ui
+                .button("Undo")
+                .on_hover_text(input_map.shortcut_text(InputAction::Undo))
+                .clicked()
+            {
+                history.undo();
+            }
+            if ui
+                .button("Redo")
+                .on_hover_text(input_map.shortcut_text(InputAction::Redo))
+                .clicked()
+            {
+                history.redo();
+            }
+            ui.toggle_value(&mut history_open, "History");
+
+            ui.separator();
+
+            for (mode, name, action) in [
+                (
+                    ManipulatorMode::Translate,
+                    "Move",
+                    InputAction::TranslateMode,
+                ),
+                (ManipulatorMode::Rotate, "Rotate", InputAction::RotateMode),
+                (ManipulatorMode::Scale, "Scale", InputAction::ScaleMode),
+            ] {
+                let text = format!("{name} ({})", input_map.shortcut_text(action));
+                ui.selectable_value(&mut edited_mode, mode, text);
+            }
+
+            ui.separator();
+
+            // Scaling is always done along local axes
+            ui.add_enabled_ui(edited_mode != ManipulatorMode::Scale, |ui| {
+                ui.selectable_value(&mut space, TransformSpace::Local, "Local");
+                ui.selectable_value(&mut space, TransformSpace::World, "World")
+                    .on_disabled_hover_text("Scale tool always uses local axes");
+            });
+
+            ui.separator();
+
+            ui.checkbox(&mut snap.enabled, RichText::new("Snap"))
+                .on_hover_text(format!(
+                    "Hold {} to temporarily invert",
+                    input_map.shortcut_text(InputAction::InvertSnap)
+                ));
+
+            ui.label("Grid:");
+            ui.add(
+                DragValue::new(&mut snap.translation_step)
+                    .speed(0.01)
+                    .clamp_range(0.001..=100.),
+            );
+
+            ui.label("Angle:");
+            ui.add(
+                DragValue::new(&mut snap.rotation_step)
+                    .speed(0.5)
+                    .clamp_range(0.1..=180.)
+                    .suffix("°"),
+            );
+
+            ui.label("Scale:");
+            ui.add(
+                DragValue::new(&mut snap.scale_step)
+                    .speed(0.01)
+                    .clamp_range(0.001..=10.),
+            );
+        });
+    });
+
+    // Toolbar sits below the menu bar, so its bottom edge is all the space taken from the top
+    let top = response.response.rect.bottom();
+    if occupied_screen_space.top != top {
+        occupied_screen_space.top = top;
+    }
+
+    if edited_mode != *mode {
+        *mode = edited_mode;
+    }
+
+    if snap != settings.snap {
+        settings.snap = snap;
+    }
+
+    if space != settings.transform_space {
+        settings.transform_space = space;
+    }
+
+    if history_open != history_window.open {
+        history_window.open = history_open;
+    }
+}